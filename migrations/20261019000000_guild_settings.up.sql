@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS "guild_settings" (
+    guild_id TEXT PRIMARY KEY NOT NULL,
+    adult_manga_preview TEXT DEFAULT 'spoiler' NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP INDEX "manga_subscriptions_manga_user";
+DROP TABLE "manga_aliases";
+ALTER TABLE "manga" DROP COLUMN title;
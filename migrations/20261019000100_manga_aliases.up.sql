@@ -0,0 +1,21 @@
+-- Add up migration script here
+
+ALTER TABLE "manga" ADD COLUMN title TEXT;
+
+CREATE TABLE IF NOT EXISTS "manga_aliases" (
+    id INTEGER PRIMARY KEY,
+    manga_dex_id TEXT NOT NULL,
+    alias TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    FOREIGN KEY (manga_dex_id) REFERENCES manga (manga_dex_id) ON DELETE CASCADE
+);
+
+-- subscribing twice used to be possible, so drop the duplicates before the index forbids them.
+DELETE FROM "manga_subscriptions"
+WHERE id NOT IN (
+    SELECT MIN(id)
+    FROM "manga_subscriptions"
+    GROUP BY manga_dex_id, user_id
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "manga_subscriptions_manga_user"
+ON "manga_subscriptions" (manga_dex_id, user_id);
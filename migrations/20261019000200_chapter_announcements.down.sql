@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE "chapter_announcement_groups";
+DROP TABLE "chapter_announcements";
+ALTER TABLE "manga" DROP COLUMN status;
@@ -0,0 +1,23 @@
+-- Add up migration script here
+
+ALTER TABLE "manga" ADD COLUMN status TEXT;
+
+CREATE TABLE IF NOT EXISTS "chapter_announcements" (
+    id INTEGER PRIMARY KEY,
+    manga_dex_id TEXT NOT NULL,
+    chapter_dex_id TEXT NOT NULL UNIQUE,
+    chapter TEXT,
+    volume TEXT,
+    published_at DATETIME,
+    announced_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS "chapter_announcements_manga_dex_id"
+ON "chapter_announcements" (manga_dex_id);
+
+CREATE TABLE IF NOT EXISTS "chapter_announcement_groups" (
+    id INTEGER PRIMARY KEY,
+    announcement_id INTEGER NOT NULL,
+    group_name TEXT NOT NULL,
+    FOREIGN KEY (announcement_id) REFERENCES chapter_announcements (id) ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS "tracker_state" (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    follows_checked_at DATETIME NOT NULL
+);
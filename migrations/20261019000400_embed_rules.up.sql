@@ -0,0 +1,17 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS "embed_rules" (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    pattern TEXT NOT NULL,
+    template TEXT NOT NULL
+);
+
+INSERT INTO "embed_rules" (name, pattern, template) VALUES
+    ('facebook', 'https?://(?:www\.)?facebook\.com/(.*)', 'https://facebed.com/${1}'),
+    ('pixiv', 'https?://(?:www\.)pixiv\.net/(?P<lang>[a-z]{2}/)?artworks/(?P<id>\d+)(?P<idx>/\d+)?', 'https://phixiv.net/${lang}artworks/${id}${idx}'),
+    ('pixiv-legacy', 'https?://(?:www\.)?pixiv\.net/member_illust\.php\?illust_id=(\d+)', 'https://phixiv.net/member_illust.php?illust_id=${1}'),
+    ('pixiv-short', 'https?://(?:www\.)pixiv\.net/i/(\d+)', 'https://www.phixiv.net/i/${1}'),
+    ('twitter', 'https?://(?:www\.)?(twitter\.com|x\.com)/([\w.-]+)/status/(\d+)', 'https://fixupx.com/${2}/status/${3}'),
+    ('tiktok', 'https?://vt\.tiktok\.com/(\w+)', 'https://kktiktok.com/${1}'),
+    ('reddit', 'https?://(?:www\.|old\.)?reddit\.com/(.*)', 'https://old.rxddit.com/${1}');
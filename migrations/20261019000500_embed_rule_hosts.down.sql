@@ -0,0 +1,10 @@
+-- Add down migration script here
+
+UPDATE "embed_rules" SET template = 'https://phixiv.net/${lang}artworks/${id}${idx}' WHERE name = 'pixiv';
+UPDATE "embed_rules" SET template = 'https://phixiv.net/member_illust.php?illust_id=${1}' WHERE name = 'pixiv-legacy';
+UPDATE "embed_rules" SET template = 'https://www.phixiv.net/i/${1}' WHERE name = 'pixiv-short';
+UPDATE "embed_rules" SET template = 'https://fixupx.com/${2}/status/${3}' WHERE name = 'twitter';
+UPDATE "embed_rules" SET template = 'https://kktiktok.com/${1}' WHERE name = 'tiktok';
+UPDATE "embed_rules" SET template = 'https://old.rxddit.com/${1}' WHERE name = 'reddit';
+
+DROP TABLE "embed_rule_hosts";
@@ -0,0 +1,39 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS "embed_rule_hosts" (
+    id INTEGER PRIMARY KEY,
+    rule_id INTEGER NOT NULL,
+    host TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    FOREIGN KEY (rule_id) REFERENCES embed_rules (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "embed_rule_hosts_rule_host"
+ON "embed_rule_hosts" (rule_id, host);
+
+UPDATE "embed_rules" SET template = 'https://{host}/${lang}artworks/${id}${idx}' WHERE name = 'pixiv';
+UPDATE "embed_rules" SET template = 'https://{host}/member_illust.php?illust_id=${1}' WHERE name = 'pixiv-legacy';
+UPDATE "embed_rules" SET template = 'https://{host}/i/${1}' WHERE name = 'pixiv-short';
+UPDATE "embed_rules" SET template = 'https://{host}/${2}/status/${3}' WHERE name = 'twitter';
+UPDATE "embed_rules" SET template = 'https://{host}/${1}' WHERE name = 'tiktok';
+UPDATE "embed_rules" SET template = 'https://{host}/${1}' WHERE name = 'reddit';
+
+INSERT INTO "embed_rule_hosts" (rule_id, host, position)
+SELECT r.id, h.host, h.position
+FROM "embed_rules" r
+INNER JOIN (
+    SELECT 'pixiv' AS name, 'phixiv.net' AS host, 0 AS position
+    UNION ALL SELECT 'pixiv', 'ppxiv.net', 1
+    UNION ALL SELECT 'pixiv-legacy', 'phixiv.net', 0
+    UNION ALL SELECT 'pixiv-legacy', 'ppxiv.net', 1
+    UNION ALL SELECT 'pixiv-short', 'www.phixiv.net', 0
+    UNION ALL SELECT 'pixiv-short', 'ppxiv.net', 1
+    UNION ALL SELECT 'twitter', 'fixupx.com', 0
+    UNION ALL SELECT 'twitter', 'fxtwitter.com', 1
+    UNION ALL SELECT 'twitter', 'vxtwitter.com', 2
+    UNION ALL SELECT 'tiktok', 'kktiktok.com', 0
+    UNION ALL SELECT 'tiktok', 'tnktok.com', 1
+    UNION ALL SELECT 'tiktok', 'vxtiktok.com', 2
+    UNION ALL SELECT 'reddit', 'old.rxddit.com', 0
+    UNION ALL SELECT 'reddit', 'old.vxreddit.com', 1
+) h ON r.name = h.name;
@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS "bot_replies" (
+    id INTEGER PRIMARY KEY,
+    source_message_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    reply_message_id TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL,
+    link TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS "bot_replies_source_message_id"
+ON "bot_replies" (source_message_id);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:www\.)?facebook\.com/(.*)'
+WHERE name = 'facebook' AND pattern = 'https?://(?:www\.)?facebook\.com/([^\s<>|`]+)';
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:www\.|old\.)?reddit\.com/(.*)'
+WHERE name = 'reddit' AND pattern = 'https?://(?:www\.|old\.)?reddit\.com/([^\s<>|`]+)';
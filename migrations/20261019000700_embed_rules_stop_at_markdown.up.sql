@@ -0,0 +1,9 @@
+-- Add up migration script here
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:www\.)?facebook\.com/([^\s<>|`]+)'
+WHERE name = 'facebook' AND pattern = 'https?://(?:www\.)?facebook\.com/(.*)';
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:www\.|old\.)?reddit\.com/([^\s<>|`]+)'
+WHERE name = 'reddit' AND pattern = 'https?://(?:www\.|old\.)?reddit\.com/(.*)';
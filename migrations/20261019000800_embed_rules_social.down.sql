@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DELETE FROM "embed_rules"
+WHERE name IN ('bluesky', 'instagram', 'threads', 'tumblr', 'tumblr-blog', 'fanbox', 'fanbox-creator');
@@ -0,0 +1,28 @@
+-- Add up migration script here
+
+INSERT INTO "embed_rules" (name, pattern, template) VALUES
+    ('bluesky', 'https?://(?:www\.)?bsky\.app/profile/(?P<handle>[\w.:-]+)/post/(?P<post>\w+)', 'https://{host}/profile/${handle}/post/${post}'),
+    ('instagram', 'https?://(?:www\.)?instagram\.com/(?P<kind>p|reels?|tv)/(?P<id>[\w-]+)', 'https://{host}/${kind}/${id}'),
+    ('threads', 'https?://(?:www\.)?threads\.(?:net|com)/(?P<user>@[\w.]+)/post/(?P<id>[\w-]+)', 'https://{host}/${user}/post/${id}'),
+    ('tumblr', 'https?://(?:www\.)?tumblr\.com/(?P<blog>[\w-]+)/(?P<id>\d+)', 'https://{host}/${blog}/${id}'),
+    ('tumblr-blog', 'https?://(?!www\.)(?P<blog>[\w-]+)\.tumblr\.com/post/(?P<id>\d+)', 'https://{host}/${blog}/${id}'),
+    ('fanbox', 'https?://(?:www\.)?fanbox\.cc/@(?P<creator>[\w-]+)/posts/(?P<id>\d+)', 'https://{host}/@${creator}/posts/${id}'),
+    ('fanbox-creator', 'https?://(?!www\.)(?P<creator>[\w-]+)\.fanbox\.cc/posts/(?P<id>\d+)', 'https://{host}/@${creator}/posts/${id}');
+
+INSERT INTO "embed_rule_hosts" (rule_id, host, position)
+SELECT r.id, h.host, h.position
+FROM "embed_rules" r
+INNER JOIN (
+    SELECT 'bluesky' AS name, 'fxbsky.app' AS host, 0 AS position
+    UNION ALL SELECT 'bluesky', 'bskx.app', 1
+    UNION ALL SELECT 'instagram', 'kkinstagram.com', 0
+    UNION ALL SELECT 'instagram', 'ddinstagram.com', 1
+    UNION ALL SELECT 'threads', 'fixthreads.net', 0
+    UNION ALL SELECT 'threads', 'vxthreads.net', 1
+    UNION ALL SELECT 'tumblr', 'tpmblr.com', 0
+    UNION ALL SELECT 'tumblr', 'txtumblr.com', 1
+    UNION ALL SELECT 'tumblr-blog', 'tpmblr.com', 0
+    UNION ALL SELECT 'tumblr-blog', 'txtumblr.com', 1
+    UNION ALL SELECT 'fanbox', 'fxfanbox.cc', 0
+    UNION ALL SELECT 'fanbox-creator', 'fxfanbox.cc', 0
+) h ON r.name = h.name;
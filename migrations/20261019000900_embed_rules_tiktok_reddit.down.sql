@@ -0,0 +1,9 @@
+-- Add down migration script here
+
+DELETE FROM "embed_rules"
+WHERE name = 'reddit-short';
+
+UPDATE "embed_rules"
+SET pattern = 'https?://vt\.tiktok\.com/(\w+)',
+    template = 'https://{host}/${1}'
+WHERE name = 'tiktok' AND pattern = 'https?://(?:(?:vt|vm)\.tiktok\.com/(?P<code>\w+)|(?:www\.|m\.)?tiktok\.com/(?P<post>@[\w.-]+/(?:video|photo)/\d+))';
@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:(?:vt|vm)\.tiktok\.com/(?P<code>\w+)|(?:www\.|m\.)?tiktok\.com/(?P<post>@[\w.-]+/(?:video|photo)/\d+))',
+    template = 'https://{host}/${code}${post}'
+WHERE name = 'tiktok' AND pattern = 'https?://vt\.tiktok\.com/(\w+)';
+
+INSERT INTO "embed_rules" (name, pattern, template) VALUES
+    ('reddit-short', 'https?://redd\.it/(?P<id>\w+)', 'https://{host}/comments/${id}');
+
+INSERT INTO "embed_rule_hosts" (rule_id, host, position)
+SELECT r.id, h.host, h.position
+FROM "embed_rules" r
+INNER JOIN "embed_rules" reddit ON reddit.name = 'reddit'
+INNER JOIN "embed_rule_hosts" h ON h.rule_id = reddit.id
+WHERE r.name = 'reddit-short';
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE "guild_settings" DROP COLUMN link_previews;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+
+ALTER TABLE "guild_settings" ADD COLUMN link_previews TEXT DEFAULT 'proxy' NOT NULL;
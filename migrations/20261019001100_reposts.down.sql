@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE "reposts";
+
+ALTER TABLE "guild_settings" DROP COLUMN link_replies;
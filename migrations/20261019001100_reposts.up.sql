@@ -0,0 +1,12 @@
+-- Add up migration script here
+
+ALTER TABLE "guild_settings" ADD COLUMN link_replies TEXT DEFAULT 'reply' NOT NULL;
+
+CREATE TABLE IF NOT EXISTS "reposts" (
+    id INTEGER PRIMARY KEY,
+    message_id TEXT NOT NULL UNIQUE,
+    channel_id TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
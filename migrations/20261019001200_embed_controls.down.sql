@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE "embed_optouts";
+DROP TABLE "disabled_embed_rules";
+DROP TABLE "embed_channels";
@@ -0,0 +1,26 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS "embed_channels" (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL UNIQUE,
+    allowed BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS "embed_channels_guild_id"
+ON "embed_channels" (guild_id);
+
+CREATE TABLE IF NOT EXISTS "disabled_embed_rules" (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    rule_id INTEGER NOT NULL,
+    FOREIGN KEY (rule_id) REFERENCES embed_rules (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "disabled_embed_rules_guild_rule"
+ON "disabled_embed_rules" (guild_id, rule_id);
+
+CREATE TABLE IF NOT EXISTS "embed_optouts" (
+    id INTEGER PRIMARY KEY,
+    user_id TEXT NOT NULL UNIQUE
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE "link_shares";
+
+ALTER TABLE "guild_settings" DROP COLUMN duplicate_window;
@@ -0,0 +1,18 @@
+-- Add up migration script here
+
+ALTER TABLE "guild_settings" ADD COLUMN duplicate_window INTEGER DEFAULT 1440 NOT NULL;
+
+CREATE TABLE IF NOT EXISTS "link_shares" (
+    id INTEGER PRIMARY KEY,
+    url_hash TEXT NOT NULL,
+    guild_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS "link_shares_channel_url_hash"
+ON "link_shares" (channel_id, url_hash);
+
+CREATE INDEX IF NOT EXISTS "link_shares_message_id"
+ON "link_shares" (message_id);
@@ -0,0 +1,9 @@
+-- Add up migration script here
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:www\.)?pixiv\.net/(?P<lang>[a-z]{2}/)?artworks/(?P<id>\d+)(?P<idx>/\d+)?'
+WHERE name = 'pixiv' AND pattern = 'https?://(?:www\.)pixiv\.net/(?P<lang>[a-z]{2}/)?artworks/(?P<id>\d+)(?P<idx>/\d+)?';
+
+UPDATE "embed_rules"
+SET pattern = 'https?://(?:www\.)?pixiv\.net/i/(\d+)'
+WHERE name = 'pixiv-short' AND pattern = 'https?://(?:www\.)pixiv\.net/i/(\d+)';
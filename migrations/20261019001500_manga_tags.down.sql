@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP INDEX "manga_tags_manga_tag";
+DROP TABLE "manga_tags";
+ALTER TABLE "manga" DROP COLUMN demographic;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+
+ALTER TABLE "manga" ADD COLUMN demographic TEXT COLLATE NOCASE;
+
+CREATE TABLE IF NOT EXISTS "manga_tags" (
+    id INTEGER PRIMARY KEY,
+    manga_dex_id TEXT NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    FOREIGN KEY (manga_dex_id) REFERENCES manga (manga_dex_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "manga_tags_manga_tag" ON "manga_tags" (manga_dex_id, tag);
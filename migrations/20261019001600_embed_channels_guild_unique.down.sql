@@ -0,0 +1,19 @@
+-- Add down migration script here
+
+CREATE TABLE "embed_channels_old" (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL UNIQUE,
+    allowed BOOLEAN NOT NULL
+);
+
+-- a channel only ever belongs to one guild, so only rows from other guilds are dropped.
+INSERT OR IGNORE INTO "embed_channels_old" (id, guild_id, channel_id, allowed)
+SELECT id, guild_id, channel_id, allowed FROM "embed_channels" ORDER BY id;
+
+DROP TABLE "embed_channels";
+
+ALTER TABLE "embed_channels_old" RENAME TO "embed_channels";
+
+CREATE INDEX IF NOT EXISTS "embed_channels_guild_id"
+ON "embed_channels" (guild_id);
@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+-- sqlite can't drop a column's unique constraint, so the table is rebuilt with the guild in it.
+CREATE TABLE "embed_channels_new" (
+    id INTEGER PRIMARY KEY,
+    guild_id TEXT NOT NULL,
+    channel_id TEXT NOT NULL,
+    allowed BOOLEAN NOT NULL
+);
+
+INSERT INTO "embed_channels_new" (id, guild_id, channel_id, allowed)
+SELECT id, guild_id, channel_id, allowed FROM "embed_channels";
+
+DROP TABLE "embed_channels";
+
+ALTER TABLE "embed_channels_new" RENAME TO "embed_channels";
+
+CREATE UNIQUE INDEX IF NOT EXISTS "embed_channels_guild_channel"
+ON "embed_channels" (guild_id, channel_id);
@@ -0,0 +1,215 @@
+use poise::serenity_prelude::{self as serenity, *};
+use sqlx::{Pool, Sqlite};
+
+use crate::Data;
+use crate::models::bot_replies::{BotReply, ReplyKind};
+
+/// how many days replies are remembered for. older messages are rarely edited or deleted.
+const REPLY_RETENTION_DAYS: i64 = 30;
+
+/// remember that `reply` was sent for `link` in `source`, so it can be cleaned up later.
+///
+/// failures are only logged, since the reply itself already went out.
+pub async fn record_reply(
+    db: &Pool<Sqlite>,
+    source: &Message,
+    reply: &Message,
+    kind: ReplyKind,
+    link: &str,
+) {
+    let source_message_id = source.id.to_string();
+    let channel_id = reply.channel_id.to_string();
+    let reply_message_id = reply.id.to_string();
+    let now = time::OffsetDateTime::now_utc();
+    let created_at = time::OffsetDateTime::new_utc(now.date(), now.time());
+
+    let _ = sqlx::query!(
+        r#"
+            INSERT INTO
+                bot_replies (source_message_id, channel_id, reply_message_id, kind, link, created_at)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (reply_message_id)
+            DO UPDATE SET
+                link = excluded.link;
+        "#,
+        source_message_id,
+        channel_id,
+        reply_message_id,
+        kind,
+        link,
+        created_at,
+    )
+    .execute(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, message_id = %source.id, "an error occurred when recording bot reply"),
+    );
+}
+
+/// stop tracking a reply, e.g. after it was deleted.
+pub async fn forget_reply(db: &Pool<Sqlite>, reply_message_id: MessageId) {
+    let reply_message_id = reply_message_id.to_string();
+
+    let _ = sqlx::query!(
+        r#"
+            DELETE FROM bot_replies
+            WHERE reply_message_id = $1;
+        "#,
+        reply_message_id,
+    )
+    .execute(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, reply_message_id = %reply_message_id, "an error occurred when forgetting bot reply"),
+    );
+}
+
+/// forget replies older than [`REPLY_RETENTION_DAYS`], returning how many were forgotten.
+pub async fn prune_old_replies(db: &Pool<Sqlite>) -> Result<u64, sqlx::Error> {
+    let now = time::OffsetDateTime::now_utc();
+    let cutoff = time::OffsetDateTime::new_utc(now.date(), now.time())
+        - time::Duration::days(REPLY_RETENTION_DAYS);
+
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM bot_replies
+            WHERE created_at < $1;
+        "#,
+        cutoff,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn get_replies(
+    db: &Pool<Sqlite>,
+    source_message_id: MessageId,
+) -> Result<Vec<BotReply>, sqlx::Error> {
+    let source_message_id = source_message_id.to_string();
+
+    sqlx::query_as!(
+        BotReply,
+        r#"
+            SELECT
+                id AS "id!",
+                source_message_id,
+                channel_id,
+                reply_message_id,
+                kind AS "kind: ReplyKind",
+                link
+            FROM bot_replies
+            WHERE source_message_id = $1
+            ORDER BY id;
+        "#,
+        source_message_id,
+    )
+    .fetch_all(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, message_id = %source_message_id, "an error occurred when fetching bot replies"),
+    )
+}
+
+fn reply_ids(reply: &BotReply) -> Option<(ChannelId, MessageId)> {
+    Some((
+        ChannelId::new(reply.channel_id.parse().ok()?),
+        MessageId::new(reply.reply_message_id.parse().ok()?),
+    ))
+}
+
+/// delete one of the bot's replies and stop tracking it.
+pub async fn delete_reply(ctx: &serenity::Context, data: &Data, reply: &BotReply) {
+    let Some((channel_id, message_id)) = reply_ids(reply) else {
+        return;
+    };
+
+    // the reply may already be gone, which is fine.
+    let _ = channel_id
+        .delete_message(ctx, message_id)
+        .await
+        .inspect_err(|e| tracing::warn!(err = ?e, message_id = %message_id, "an error occurred when deleting bot reply"));
+
+    forget_reply(&data.db, message_id).await;
+}
+
+/// delete the bot's replies to a message that was deleted.
+pub async fn delete_replies(
+    ctx: &serenity::Context,
+    data: &Data,
+    source_message_id: MessageId,
+) -> Result<(), sqlx::Error> {
+    for reply in get_replies(&data.db, source_message_id).await? {
+        delete_reply(ctx, data, &reply).await;
+    }
+
+    Ok(())
+}
+
+/// the full message behind an edit, if its content was actually edited by a user.
+///
+/// embed unfurls and the bot's own embed suppression also fire message updates, but without new
+/// content or an edit timestamp.
+pub async fn edited_message(
+    ctx: &serenity::Context,
+    new: Option<&Message>,
+    event: &MessageUpdateEvent,
+) -> Result<Option<Message>, serenity::Error> {
+    if event.content.is_none() || event.edited_timestamp.is_none() {
+        return Ok(None);
+    }
+
+    if event.author.as_ref().is_some_and(|author| author.bot) {
+        return Ok(None);
+    }
+
+    let mut message = match new {
+        Some(message) => message.clone(),
+        None => event
+            .channel_id
+            .message(ctx, event.id)
+            .await
+            .inspect_err(|e| tracing::error!(err = ?e, message_id = %event.id, "an error occurred when fetching edited message"))?,
+    };
+
+    // messages fetched over http don't carry their guild.
+    if message.guild_id.is_none() {
+        message.guild_id = event.guild_id;
+    }
+
+    if message.author.bot {
+        return Ok(None);
+    }
+
+    Ok(Some(message))
+}
+
+/// delete the replies to an edited message whose links are no longer in it, returning the rest.
+///
+/// embed replies are kept, since the embed handler edits them in place.
+pub async fn prune_replies(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &Message,
+) -> Result<Vec<BotReply>, sqlx::Error> {
+    let mut kept = vec![];
+
+    for reply in get_replies(&data.db, message.id).await? {
+        if reply.kind == ReplyKind::Embed || message.content.contains(&reply.link) {
+            kept.push(reply);
+        } else {
+            delete_reply(ctx, data, &reply).await;
+        }
+    }
+
+    Ok(kept)
+}
+
+/// whether a message already has a reply of this kind for `link`.
+pub fn has_reply(replies: &[BotReply], kind: ReplyKind, link: &str) -> bool {
+    replies
+        .iter()
+        .any(|reply| reply.kind == kind && reply.link == link)
+}
@@ -1,14 +1,102 @@
-use mangadex_api_types_rust::MangaFeedSortOrder;
+use std::collections::HashSet;
+
 use poise::serenity_prelude::*;
+use sqlx::{Pool, Sqlite};
 
-use crate::constants::manga::MD_BLOCKED_LIST;
-use crate::models::manga::Manga;
+use crate::mangadex::{ChapterInfo, MangaDexApi, MangaInfo};
+use crate::models::manga::{Manga, TrackerSource};
 use crate::{Data, Error};
 
+/// page size used when walking the followed manga feed.
+const FOLLOWS_FEED_PAGE_SIZE: u32 = 100;
+
+/// mangadex refuses requests where `offset + limit` goes past this.
+const FOLLOWS_FEED_MAX_OFFSET: u32 = 10_000;
+
+/// a chapter that should be announced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewChapter {
+    pub manga_id: uuid::Uuid,
+    pub manga_title: String,
+    pub chapter: ChapterInfo,
+    /// how many users are subscribed to the manga.
+    pub subscribers: i64,
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn chapter_tracker(http: &Http, data: &Data) -> Result<(), Error> {
-    tracing::info!("started checking for new chapters!");
+    tracing::info!(source = ?data.tracker_source, "started checking for new chapters!");
+
+    let Some(api) = data.mangadex() else {
+        return Err("mangadex client is not initialized.".into());
+    };
+
+    let now = time::OffsetDateTime::now_utc();
+    let checked_at = time::OffsetDateTime::new_utc(now.date(), now.time());
+    let chapter_list = detect_new_chapters(&api, &data.db, data.tracker_source).await?;
+
+    if chapter_list.is_empty() {
+        tracing::info!("finished checking for new chapters!");
+
+        return Ok(());
+    }
+
+    let chunks = chapter_list.chunks(10);
+
+    for chunk in chunks {
+        let content = if chunk.len() > 1 {
+            "New chapters are out!"
+        } else {
+            "A new chapter is out!"
+        };
+
+        data.manga_update_channel_id
+            .unwrap()
+            .send_message(
+                &http,
+                CreateMessage::default()
+                    .content(content)
+                    .embeds(chunk.iter().map(chapter_embed).collect()),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+        // only chapters that made it to discord count as announced, anything after a failed
+        // send gets picked up again on the next poll.
+        for new_chapter in chunk {
+            record_announcement(&data.db, new_chapter).await?;
+        }
+    }
+
+    if data.tracker_source == TrackerSource::Follows {
+        update_follows_checked_at(&data.db, checked_at).await?;
+    }
+
+    tracing::info!("finished checking for new chapters!");
+
+    Ok(())
+}
+
+/// finds chapters that haven't been announced yet, without sending anything to discord.
+///
+/// the chapters only get recorded in the announcement history by [`record_announcement`] once
+/// they're sent, and the feed bounds stay put until then.
+pub async fn detect_new_chapters(
+    api: &dyn MangaDexApi,
+    db: &Pool<Sqlite>,
+    source: TrackerSource,
+) -> Result<Vec<NewChapter>, Error> {
+    match source {
+        TrackerSource::List => check_manga_list(api, db).await,
+        TrackerSource::Follows => check_follows_feed(api, db).await,
+    }
+}
 
+/// checks every manga in the database for a new chapter.
+async fn check_manga_list(
+    api: &dyn MangaDexApi,
+    db: &Pool<Sqlite>,
+) -> Result<Vec<NewChapter>, Error> {
     let manga_list = sqlx::query_as!(
         Manga,
         r#"
@@ -16,28 +104,21 @@ pub async fn chapter_tracker(http: &Http, data: &Data) -> Result<(), Error> {
                 id,
                 manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
                 last_updated,
-                last_chapter_date
+                last_chapter_date,
+                title,
+                status
             FROM manga;
         "#
     )
-    .fetch_all(&data.db)
+    .fetch_all(db)
     .await?;
 
-    let mut chapter_list: Vec<CreateEmbed> = vec![];
+    let mut chapter_list: Vec<NewChapter> = vec![];
 
     for db_manga in manga_list {
         let uuid = db_manga.manga_dex_id;
 
-        let manga = match data
-            .md
-            .as_ref()
-            .unwrap()
-            .manga()
-            .id(uuid.into())
-            .get()
-            .send()
-            .await
-        {
+        let manga = match api.manga(uuid.into_uuid()).await {
             Ok(manga) => manga,
             Err(e) => {
                 tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga");
@@ -45,45 +126,17 @@ pub async fn chapter_tracker(http: &Http, data: &Data) -> Result<(), Error> {
             }
         };
 
-        let manga = manga.data.attributes;
-
-        let title = match manga.title.get(&mangadex_api_types_rust::Language::English) {
-            Some(en_title) => en_title,
-            None => {
-                match manga
-                    .title
-                    .get(&mangadex_api_types_rust::Language::JapaneseRomanized)
-                {
-                    Some(jp_ro) => jp_ro,
-                    None => manga
-                        .title
-                        .get(&mangadex_api_types_rust::Language::Japanese)
-                        .unwrap(),
-                }
-            }
-        };
+        cache_manga_tags(db, &manga).await.inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when caching manga tags"),
+        )?;
 
-        let chapter_feed = match data
-            .md
-            .as_ref()
-            .unwrap()
-            .manga()
-            .id(db_manga.manga_dex_id.into())
-            .feed()
-            .get()
-            .add_translated_language(&mangadex_api_types_rust::Language::English)
-            .publish_at_since(mangadex_api_types_rust::MangaDexDateTime::new(
-                &db_manga.last_updated,
-            ))
-            .order(MangaFeedSortOrder::Chapter(
-                mangadex_api_types_rust::OrderDirection::Descending,
-            ))
-            .excluded_groups(MD_BLOCKED_LIST.clone())
-            .limit(1u32)
-            .send()
+        let title = manga.title.unwrap_or_else(|| uuid.to_string());
+
+        let chapter = match api
+            .latest_chapter(uuid.into_uuid(), Some(db_manga.last_updated))
             .await
         {
-            Ok(feed) => feed,
+            Ok(chapter) => chapter,
             Err(e) => {
                 tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching chapter feed");
                 continue;
@@ -93,99 +146,783 @@ pub async fn chapter_tracker(http: &Http, data: &Data) -> Result<(), Error> {
         let mut db_manga_insert = db_manga;
         let now = time::OffsetDateTime::now_utc();
 
-        if chapter_feed.result == mangadex_api_types_rust::ResultType::Ok
-            && !chapter_feed.data.is_empty()
-        {
-            let chapter = chapter_feed.data.first().unwrap();
-
-            let chapter_data = &chapter.attributes;
-
-            match &chapter_data.chapter {
-                Some(chap) => {
-                    tracing::info!(uuid = %uuid, "got chapter for manga");
-
-                    let mut vol_chap_str = match &chapter_data.volume {
-                        Some(vol) => format!("Vol. {}, Ch. {}", vol, chap),
-                        None => format!("Ch. {}", chap),
-                    };
-
-                    if let Some(chapter_title) = &chapter_data.title {
-                        vol_chap_str = vol_chap_str + &format!(" - {}", chapter_title);
-                    }
-
-                    let embed = CreateEmbed::default()
-                        .title(title)
-                        .url(format!("https://mangadex.org/chapter/{}", chapter.id))
-                        .description(vol_chap_str)
-                        .image(format!(
-                            "https://og.mangadex.org/og-image/chapter/{}",
-                            chapter.id
-                        ));
-
-                    if let Some(timestamp) = chapter_data.publish_at {
-                        db_manga_insert.last_chapter_date = Some(time::OffsetDateTime::new_utc(
-                            timestamp.as_ref().date(),
-                            timestamp.as_ref().time(),
-                        ))
-                    }
-
-                    chapter_list.push(embed);
+        db_manga_insert.title = Some(title.clone());
+        db_manga_insert.status = Some(manga.status);
+
+        let mut announcing = false;
+
+        if let Some(chapter) = chapter {
+            if chapter.chapter.is_none() {
+                continue;
+            }
+
+            if chapter.is_blocked() {
+                tracing::info!(uuid = %uuid, chapter = %chapter.id, "skipping chapter from a blocked group");
+            } else {
+                if chapter.publish_at.is_some() {
+                    db_manga_insert.last_chapter_date = chapter.publish_at;
                 }
 
-                None => {
-                    continue;
+                if let Some(new_chapter) = new_chapter(db, &title, uuid, chapter).await? {
+                    chapter_list.push(new_chapter);
+                    announcing = true;
                 }
-            };
+            }
         }
 
-        db_manga_insert.last_updated = time::OffsetDateTime::new_utc(now.date(), now.time());
+        if !announcing {
+            db_manga_insert.last_updated = time::OffsetDateTime::new_utc(now.date(), now.time());
+        }
 
         sqlx::query!(
             r#"
                 INSERT INTO
-                    manga (id, manga_dex_id, last_updated, last_chapter_date)
+                    manga (id, manga_dex_id, last_updated, last_chapter_date, title, status)
                 VALUES
-                    ($1, $2, $3, $4)
+                    ($1, $2, $3, $4, $5, $6)
                 ON CONFLICT (manga_dex_id)
                 DO UPDATE SET
                     last_updated = excluded.last_updated,
-                    last_chapter_date = excluded.last_chapter_date;
+                    last_chapter_date = excluded.last_chapter_date,
+                    title = excluded.title,
+                    status = excluded.status;
             "#,
             db_manga_insert.id,
             db_manga_insert.manga_dex_id,
             db_manga_insert.last_updated,
             db_manga_insert.last_chapter_date,
+            db_manga_insert.title,
+            db_manga_insert.status,
         )
-        .execute(&data.db)
+        .execute(db)
         .await?;
     }
 
+    Ok(chapter_list)
+}
+
+/// reads the logged-in user's followed manga feed for chapters published since the last check.
+///
+/// the feed is newest first, so only the first chapter seen for each manga gets announced.
+async fn check_follows_feed(
+    api: &dyn MangaDexApi,
+    db: &Pool<Sqlite>,
+) -> Result<Vec<NewChapter>, Error> {
+    let now = time::OffsetDateTime::now_utc();
+    let checked_at = time::OffsetDateTime::new_utc(now.date(), now.time());
+
+    let last_checked = sqlx::query_scalar!(
+        r#"
+            SELECT follows_checked_at
+            FROM tracker_state
+            WHERE id = 1;
+        "#
+    )
+    .fetch_optional(db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching tracker state"))?;
+
+    // on the very first run, start from now instead of announcing the whole backlog.
+    let Some(last_checked) = last_checked else {
+        tracing::info!("no previous follows feed check found, starting from now.");
+        update_follows_checked_at(db, checked_at).await?;
+
+        return Ok(vec![]);
+    };
+
+    let mut chapters: Vec<ChapterInfo> = vec![];
+    let mut offset = 0u32;
+
+    loop {
+        let page = api
+            .follows_feed(last_checked, offset, FOLLOWS_FEED_PAGE_SIZE)
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, offset, "an error occurred when fetching followed manga feed"),
+            )?;
+
+        let fetched = page.chapters.len() as u32;
+        chapters.extend(page.chapters);
+        offset += fetched;
+
+        if fetched == 0
+            || offset >= page.total
+            || offset + FOLLOWS_FEED_PAGE_SIZE > FOLLOWS_FEED_MAX_OFFSET
+        {
+            break;
+        }
+    }
+
+    tracing::info!(
+        count = chapters.len(),
+        "fetched chapters from followed manga feed"
+    );
+
+    let mut seen = HashSet::new();
+    let mut chapter_list: Vec<NewChapter> = vec![];
+
+    for chapter in chapters {
+        if chapter.is_blocked() {
+            continue;
+        }
+
+        let Some(manga) = chapter.manga.clone() else {
+            continue;
+        };
+
+        let Some(title) = manga.title.clone() else {
+            continue;
+        };
+
+        if !seen.insert(chapter.manga_id) {
+            continue;
+        }
+
+        let uuid = manga.id.hyphenated();
+
+        // the manga row has to exist before the announcement history and subscriptions can
+        // reference it, so followed titles get mirrored into the database as they show up.
+        sqlx::query!(
+            r#"
+                INSERT INTO
+                    manga (manga_dex_id, last_updated, title, status)
+                VALUES
+                    ($1, $2, $3, $4)
+                ON CONFLICT (manga_dex_id)
+                DO UPDATE SET
+                    last_updated = excluded.last_updated,
+                    title = excluded.title,
+                    status = excluded.status;
+            "#,
+            uuid,
+            checked_at,
+            title,
+            manga.status,
+        )
+        .execute(db)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when updating manga in database"),
+        )?;
+
+        cache_manga_tags(db, &manga).await.inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when caching manga tags"),
+        )?;
+
+        if chapter.chapter.is_none() {
+            continue;
+        }
+
+        if chapter.publish_at.is_some() {
+            sqlx::query!(
+                r#"
+                    UPDATE manga
+                    SET last_chapter_date = $1
+                    WHERE manga_dex_id = $2;
+                "#,
+                chapter.publish_at,
+                uuid,
+            )
+            .execute(db)
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when updating manga in database"),
+            )?;
+        }
+
+        if let Some(new_chapter) = new_chapter(db, &title, uuid, chapter).await? {
+            chapter_list.push(new_chapter);
+        }
+    }
+
+    // with chapters to announce, the tracker moves the check forward once they're sent.
     if chapter_list.is_empty() {
-        tracing::info!("finished checking for new chapters!");
+        update_follows_checked_at(db, checked_at).await?;
+    }
 
-        return Ok(());
+    Ok(chapter_list)
+}
+
+/// caches the demographic and tags of a tracked manga, so `s>manga random` can filter on them
+/// without asking mangadex.
+pub async fn cache_manga_tags(db: &Pool<Sqlite>, manga: &MangaInfo) -> Result<(), sqlx::Error> {
+    let uuid = manga.id.hyphenated();
+    let mut tx = db.begin().await?;
+
+    sqlx::query!(
+        r#"
+            UPDATE manga
+            SET demographic = $1
+            WHERE manga_dex_id = $2;
+        "#,
+        manga.demographic,
+        uuid,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM manga_tags
+            WHERE manga_dex_id = $1;
+        "#,
+        uuid,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for tag in &manga.tags {
+        sqlx::query!(
+            r#"
+                INSERT INTO
+                    manga_tags (manga_dex_id, tag)
+                VALUES
+                    ($1, $2)
+                ON CONFLICT (manga_dex_id, tag)
+                DO NOTHING;
+            "#,
+            uuid,
+            tag,
+        )
+        .execute(&mut *tx)
+        .await?;
     }
 
-    let chunks = chapter_list.chunks(10);
+    tx.commit().await
+}
 
-    for chunk in chunks {
-        data.manga_update_channel_id
-            .unwrap()
-            .send_message(
-                &http,
-                CreateMessage::default()
-                    .content(if chunk.len() > 1 {
-                        "New chapters are out!"
-                    } else {
-                        "A new chapter is out!"
-                    })
-                    .embeds(chunk.to_vec()),
+async fn update_follows_checked_at(
+    db: &Pool<Sqlite>,
+    checked_at: time::OffsetDateTime,
+) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+            INSERT INTO
+                tracker_state (id, follows_checked_at)
+            VALUES
+                (1, $1)
+            ON CONFLICT (id)
+            DO UPDATE SET
+                follows_checked_at = excluded.follows_checked_at;
+        "#,
+        checked_at,
+    )
+    .execute(db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when updating tracker state"))?;
+
+    Ok(())
+}
+
+/// counts the subscribers of a chapter that hasn't been announced yet.
+///
+/// the feed bounds are inclusive, so a chapter can show up in two polls in a row. returns
+/// `None` when the chapter has already been announced.
+async fn new_chapter(
+    db: &Pool<Sqlite>,
+    title: &str,
+    uuid: uuid::fmt::Hyphenated,
+    chapter: ChapterInfo,
+) -> Result<Option<NewChapter>, Error> {
+    tracing::info!(uuid = %uuid, "got chapter for manga");
+
+    let chapter_dex_id = chapter.id.hyphenated();
+
+    let announced = sqlx::query_scalar!(
+        r#"
+            SELECT id
+            FROM chapter_announcements
+            WHERE chapter_dex_id = $1;
+        "#,
+        chapter_dex_id
+    )
+    .fetch_optional(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching chapter announcements from database"),
+    )?;
+
+    if announced.is_some() {
+        tracing::info!(uuid = %uuid, chapter = %chapter_dex_id, "chapter was already announced");
+        return Ok(None);
+    }
+
+    let subscribers = sqlx::query_scalar!(
+        r#"
+            SELECT COUNT(*) AS count
+            FROM manga_subscriptions
+            WHERE manga_dex_id = $1;
+        "#,
+        uuid
+    )
+    .fetch_one(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching subscriptions from database"),
+    )?;
+
+    Ok(Some(NewChapter {
+        manga_id: uuid.into_uuid(),
+        manga_title: title.to_string(),
+        chapter,
+        subscribers,
+    }))
+}
+
+/// records a sent chapter in the announcement history and moves the manga's feed bound past it.
+async fn record_announcement(db: &Pool<Sqlite>, new_chapter: &NewChapter) -> Result<(), Error> {
+    let chapter = &new_chapter.chapter;
+    let uuid = new_chapter.manga_id.hyphenated();
+    let chapter_dex_id = chapter.id.hyphenated();
+    let now = time::OffsetDateTime::now_utc();
+    let announced_at = time::OffsetDateTime::new_utc(now.date(), now.time());
+
+    let mut tx = db.begin().await?;
+
+    let announcement = sqlx::query!(
+        r#"
+            INSERT INTO
+                chapter_announcements (manga_dex_id, chapter_dex_id, chapter, volume, published_at, announced_at)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (chapter_dex_id)
+            DO NOTHING;
+        "#,
+        uuid,
+        chapter_dex_id,
+        chapter.chapter,
+        chapter.volume,
+        chapter.publish_at,
+        announced_at,
+    )
+    .execute(&mut *tx)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when recording chapter announcement"),
+    )?;
+
+    if announcement.rows_affected() > 0 {
+        let announcement_id = announcement.last_insert_rowid();
+
+        for (_, group_name) in &chapter.groups {
+            sqlx::query!(
+                r#"
+                    INSERT INTO
+                        chapter_announcement_groups (announcement_id, group_name)
+                    VALUES
+                        ($1, $2);
+                "#,
+                announcement_id,
+                group_name,
             )
+            .execute(&mut *tx)
             .await
-            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+            .inspect_err(
+                |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when recording chapter announcement"),
+            )?;
+        }
     }
 
-    tracing::info!("finished checking for new chapters!");
+    sqlx::query!(
+        r#"
+            UPDATE manga
+            SET last_updated = $1
+            WHERE manga_dex_id = $2;
+        "#,
+        announced_at,
+        uuid,
+    )
+    .execute(&mut *tx)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when updating manga in database"),
+    )?;
+
+    tx.commit().await?;
 
     Ok(())
 }
+
+fn chapter_embed(new_chapter: &NewChapter) -> CreateEmbed {
+    let chapter = &new_chapter.chapter;
+    let title = &new_chapter.manga_title;
+
+    let mut vol_chap_str = match (&chapter.volume, &chapter.chapter) {
+        (Some(vol), Some(chap)) => format!("Vol. {}, Ch. {}", vol, chap),
+        (None, Some(chap)) => format!("Ch. {}", chap),
+        _ => "Oneshot".to_string(),
+    };
+
+    if let Some(chapter_title) = &chapter.title {
+        vol_chap_str = vol_chap_str + &format!(" - {}", chapter_title);
+    }
+
+    let groups = chapter
+        .groups
+        .iter()
+        .map(|(id, name)| format!("[{}](https://mangadex.org/group/{})", name, id))
+        .collect::<Vec<String>>();
+
+    let uploader = chapter
+        .uploader
+        .as_ref()
+        .map(|(id, username)| format!("[{}](https://mangadex.org/user/{})", username, id));
+
+    let mut embed = CreateEmbed::default()
+        .title(title)
+        .url(format!("https://mangadex.org/chapter/{}", chapter.id))
+        .description(vol_chap_str)
+        .image(format!(
+            "https://og.mangadex.org/og-image/chapter/{}",
+            chapter.id
+        ))
+        .field(
+            "series",
+            format!(
+                "[{}](https://mangadex.org/title/{})",
+                title, new_chapter.manga_id
+            ),
+            false,
+        )
+        .field(
+            if groups.len() > 1 { "groups" } else { "group" },
+            if groups.is_empty() {
+                "no group".to_string()
+            } else {
+                groups.join(", ")
+            },
+            true,
+        )
+        .field(
+            "uploader",
+            uploader.unwrap_or_else(|| "unknown".to_string()),
+            true,
+        )
+        .field("pages", chapter.pages.to_string(), true)
+        .field("language", &chapter.language, true)
+        .footer(CreateEmbedFooter::new(match new_chapter.subscribers {
+            1 => "1 user subscribed".to_string(),
+            n => format!("{} users subscribed", n),
+        }));
+
+    if let Some(published_at) = chapter
+        .publish_at
+        .and_then(|t| Timestamp::from_unix_timestamp(t.unix_timestamp()).ok())
+    {
+        embed = embed.timestamp(published_at);
+    }
+
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::constants::manga::MANGAPLUS;
+    use crate::mangadex::fake::{FakeMangaDex, chapter, manga_info};
+
+    async fn test_db() -> Pool<Sqlite> {
+        // every connection to `sqlite::memory:` gets its own database, so only allow one.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        db
+    }
+
+    async fn track(db: &Pool<Sqlite>, id: Uuid, last_updated: time::OffsetDateTime) {
+        sqlx::query("INSERT INTO manga (manga_dex_id, last_updated) VALUES ($1, $2);")
+            .bind(id.hyphenated().to_string())
+            .bind(last_updated)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    async fn last_updated(db: &Pool<Sqlite>, id: Uuid) -> time::OffsetDateTime {
+        sqlx::query_scalar("SELECT last_updated FROM manga WHERE manga_dex_id = $1;")
+            .bind(id.hyphenated().to_string())
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn announcement_count(db: &Pool<Sqlite>) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM chapter_announcements;")
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    fn week_ago() -> time::OffsetDateTime {
+        let then = time::OffsetDateTime::now_utc() - time::Duration::weeks(1);
+        time::OffsetDateTime::new_utc(then.date(), then.time())
+    }
+
+    #[tokio::test]
+    async fn announces_new_chapter() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+        track(&db, manga_id, week_ago()).await;
+
+        sqlx::query("INSERT INTO manga_subscriptions (manga_dex_id, user_id) VALUES ($1, '42');")
+            .bind(manga_id.hyphenated().to_string())
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let mut api = FakeMangaDex::default().with_manga(manga_id, "some manga");
+        let new_chapter = chapter(manga_id, "12");
+        api.latest_chapters.insert(manga_id, new_chapter.clone());
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].manga_title, "some manga");
+        assert_eq!(found[0].chapter, new_chapter);
+        assert_eq!(found[0].subscribers, 1);
+        assert_eq!(announcement_count(&db).await, 0);
+
+        record_announcement(&db, &found[0]).await.unwrap();
+        assert_eq!(announcement_count(&db).await, 1);
+
+        let title: Option<String> =
+            sqlx::query_scalar("SELECT title FROM manga WHERE manga_dex_id = $1;")
+                .bind(manga_id.hyphenated().to_string())
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(title.as_deref(), Some("some manga"));
+    }
+
+    #[tokio::test]
+    async fn records_each_chapter_once() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+        track(&db, manga_id, week_ago()).await;
+
+        let mut api = FakeMangaDex::default().with_manga(manga_id, "some manga");
+        api.latest_chapters
+            .insert(manga_id, chapter(manga_id, "12"));
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+        record_announcement(&db, &found[0]).await.unwrap();
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert!(found.is_empty());
+        assert_eq!(announcement_count(&db).await, 1);
+    }
+
+    #[tokio::test]
+    async fn unsent_chapters_are_found_again() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+        let before = week_ago();
+        track(&db, manga_id, before).await;
+
+        let mut api = FakeMangaDex::default().with_manga(manga_id, "some manga");
+        api.latest_chapters
+            .insert(manga_id, chapter(manga_id, "12"));
+
+        detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert_eq!(last_updated(&db, manga_id).await, before);
+        assert_eq!(announcement_count(&db).await, 0);
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn no_new_chapter_only_advances_last_updated() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+        let before = week_ago();
+        track(&db, manga_id, before).await;
+
+        let api = FakeMangaDex::default().with_manga(manga_id, "some manga");
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert!(found.is_empty());
+        assert!(last_updated(&db, manga_id).await > before);
+        assert_eq!(announcement_count(&db).await, 0);
+    }
+
+    #[tokio::test]
+    async fn skips_blocked_groups() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+        track(&db, manga_id, week_ago()).await;
+
+        let mut blocked = chapter(manga_id, "12");
+        blocked.groups = vec![(*MANGAPLUS, "MANGA Plus".to_string())];
+
+        let mut api = FakeMangaDex::default().with_manga(manga_id, "some manga");
+        api.latest_chapters.insert(manga_id, blocked);
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert!(found.is_empty());
+        assert_eq!(announcement_count(&db).await, 0);
+    }
+
+    #[tokio::test]
+    async fn skips_chapters_without_a_number() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+        let before = week_ago();
+        track(&db, manga_id, before).await;
+
+        let mut oneshot = chapter(manga_id, "1");
+        oneshot.chapter = None;
+
+        let mut api = FakeMangaDex::default().with_manga(manga_id, "some manga");
+        api.latest_chapters.insert(manga_id, oneshot);
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert!(found.is_empty());
+        assert_eq!(last_updated(&db, manga_id).await, before);
+    }
+
+    #[tokio::test]
+    async fn failed_lookups_do_not_stop_other_manga() {
+        let db = test_db().await;
+        let broken_id = Uuid::from_u128(1);
+        let working_id = Uuid::from_u128(2);
+        let before = week_ago();
+        track(&db, broken_id, before).await;
+        track(&db, working_id, before).await;
+
+        let mut api = FakeMangaDex::default()
+            .with_manga(broken_id, "broken manga")
+            .with_manga(working_id, "working manga");
+        api.failing.insert(broken_id);
+        api.latest_chapters
+            .insert(working_id, chapter(working_id, "3"));
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::List)
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].manga_id, working_id);
+        assert_eq!(last_updated(&db, broken_id).await, before);
+    }
+
+    #[tokio::test]
+    async fn follows_feed_starts_from_now_on_first_run() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1);
+
+        let mut api = FakeMangaDex::default();
+
+        let mut followed = chapter(manga_id, "5");
+        followed.manga = Some(manga_info(manga_id, "followed manga"));
+        api.follows.push(followed);
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::Follows)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+
+        let found = detect_new_chapters(&api, &db, TrackerSource::Follows)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].manga_title, "followed manga");
+    }
+
+    #[tokio::test]
+    async fn follows_feed_announces_newest_chapter_per_manga() {
+        let db = test_db().await;
+        let first_id = Uuid::from_u128(1);
+        let second_id = Uuid::from_u128(2);
+
+        let mut api = FakeMangaDex::default();
+
+        let mut newest = chapter(first_id, "11");
+        newest.manga = Some(manga_info(first_id, "first manga"));
+        let mut older = chapter(first_id, "10");
+        older.manga = newest.manga.clone();
+        let mut blocked = chapter(second_id, "2");
+        blocked.manga = Some(manga_info(second_id, "second manga"));
+        blocked.groups = vec![(*MANGAPLUS, "MANGA Plus".to_string())];
+
+        api.follows = vec![newest.clone(), blocked, older];
+
+        detect_new_chapters(&api, &db, TrackerSource::Follows)
+            .await
+            .unwrap();
+        let found = detect_new_chapters(&api, &db, TrackerSource::Follows)
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].chapter, newest);
+
+        let tracked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM manga;")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(tracked, 1);
+    }
+
+    #[tokio::test]
+    async fn follows_feed_error_keeps_last_check() {
+        let db = test_db().await;
+        let api = FakeMangaDex {
+            follows_fail: true,
+            ..Default::default()
+        };
+
+        detect_new_chapters(&api, &db, TrackerSource::Follows)
+            .await
+            .unwrap();
+
+        let checked_at: time::OffsetDateTime =
+            sqlx::query_scalar("SELECT follows_checked_at FROM tracker_state WHERE id = 1;")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+
+        assert!(
+            detect_new_chapters(&api, &db, TrackerSource::Follows)
+                .await
+                .is_err()
+        );
+
+        let checked_at_after: time::OffsetDateTime =
+            sqlx::query_scalar("SELECT follows_checked_at FROM tracker_state WHERE id = 1;")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(checked_at, checked_at_after);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::Context;
 
+pub mod embed;
 pub mod fluff;
 pub mod gas_prices;
 pub mod help;
 pub mod manga;
 pub mod quote;
+pub mod settings;
 pub mod status;
 pub mod zenless;
 
@@ -0,0 +1,711 @@
+use poise::serenity_prelude::*;
+
+use crate::embed_controls::{forget_embed_controls, get_embed_controls};
+use crate::embed_rules::{check_fixer_hosts, get_embed_rules, is_host_healthy, reload_embed_rules};
+use crate::models::embeds::CompiledEmbedRule;
+use crate::{Context, Error};
+
+/// commands related to link embed fixing.
+#[tracing::instrument(skip_all)]
+#[poise::command(
+    prefix_command,
+    subcommand_required,
+    subcommands("rule", "channel", "optout", "optin")
+)]
+pub async fn embed(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// manage the rules used to rewrite links into embed-friendly ones.
+#[tracing::instrument(skip_all)]
+#[poise::command(
+    prefix_command,
+    subcommand_required,
+    aliases("rules"),
+    subcommands("add", "list", "remove", "hosts", "test", "enable", "disable")
+)]
+pub async fn rule(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// strips the backticks people wrap patterns in to stop discord from formatting them.
+fn strip_code(input: &str) -> &str {
+    let input = input.trim();
+
+    input
+        .strip_prefix('`')
+        .and_then(|input| input.strip_suffix('`'))
+        .unwrap_or(input)
+}
+
+async fn reply(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(content),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// add a rule. the template refers to the pattern's capture groups as `${name}` or `${1}`, and
+/// to the rule's first healthy fixer host as `{host}`.
+///
+/// the pattern goes last and is taken as-is, so backslashes don't need escaping, e.g.
+/// `s>embed rule add bsky https://bskx.app/${path} https?://bsky\.app/(?P<path>\S+)`
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, owners_only)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[description = "name of the rule."] name: String,
+    #[description = "url template to rewrite matching links into."] template: String,
+    #[description = "regex matching the links to rewrite."]
+    #[rest]
+    pattern: String,
+) -> Result<(), Error> {
+    let pattern = strip_code(&pattern);
+    let template = strip_code(&template);
+
+    if let Err(e) = CompiledEmbedRule::new(&name, pattern, template, vec![]) {
+        return reply(ctx, format!("couldn't add the rule: {}", e)).await;
+    }
+
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO
+                embed_rules (name, pattern, template)
+            VALUES
+                ($1, $2, $3);
+        "#,
+        name,
+        pattern,
+        template,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, name = %name, "an error occurred when adding embed rule"),
+    );
+
+    if let Err(e) = result {
+        if !e
+            .as_database_error()
+            .is_some_and(|e| e.is_unique_violation())
+        {
+            return Err(e.into());
+        }
+
+        return reply(ctx, format!("a rule named \"{name}\" already exists.")).await;
+    }
+
+    reload_embed_rules(ctx.data()).await?;
+
+    reply(ctx, format!("added embed rule \"{name}\".")).await
+}
+
+/// list the embed rules, in the order they are applied.
+#[tracing::instrument(skip_all)]
+#[poise::command(prefix_command)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let rules = get_embed_rules(&ctx.data().db).await?;
+    let disabled_rules = match ctx.guild_id() {
+        Some(guild_id) => {
+            get_embed_controls(ctx.data(), guild_id)
+                .await?
+                .disabled_rules
+        }
+        None => vec![],
+    };
+    let health = ctx.data().fixer_health.read().unwrap().clone();
+
+    if rules.is_empty() {
+        return reply(ctx, "there are no embed rules.".to_string()).await;
+    }
+
+    let description = rules
+        .iter()
+        .map(|rule| {
+            let mut entry = format!(
+                "**{}**{}\n`{}`\n→ `{}`",
+                rule.name,
+                if disabled_rules.contains(&rule.name) {
+                    " (disabled here)"
+                } else {
+                    ""
+                },
+                rule.pattern,
+                rule.template
+            );
+
+            if !rule.hosts.is_empty() {
+                let hosts = rule
+                    .hosts
+                    .iter()
+                    .map(|host| match health.get(host) {
+                        Some(true) => format!("✅ `{host}`"),
+                        Some(false) => format!("❌ `{host}`"),
+                        None => format!("❔ `{host}`"),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                entry = format!("{entry}\nhosts: {hosts}");
+            }
+
+            entry
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .embed(
+                CreateEmbed::default()
+                    .title("embed rules")
+                    .description(description),
+            ),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// remove an embed rule.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, owners_only)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "name of the rule."] name: String,
+) -> Result<(), Error> {
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM embed_rules
+            WHERE name = $1;
+        "#,
+        name,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, name = %name, "an error occurred when removing embed rule"),
+    )?;
+
+    if result.rows_affected() == 0 {
+        return reply(ctx, format!("there is no rule named \"{name}\".")).await;
+    }
+
+    // the rule is gone from every guild's disabled rules too.
+    ctx.data().embed_controls.write().unwrap().clear();
+    reload_embed_rules(ctx.data()).await?;
+
+    reply(ctx, format!("removed embed rule \"{name}\".")).await
+}
+
+/// set the fixer hosts of a rule, in order of preference. leave them out to clear the list.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, owners_only)]
+pub async fn hosts(
+    ctx: Context<'_>,
+    #[description = "name of the rule."] name: String,
+    #[description = "fixer hosts, e.g. `fixupx.com fxtwitter.com`."] hosts: Vec<String>,
+) -> Result<(), Error> {
+    let hosts = hosts
+        .iter()
+        .map(|host| {
+            host.trim()
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .trim_end_matches('/')
+                .to_lowercase()
+        })
+        .filter(|host| !host.is_empty())
+        .collect::<Vec<String>>();
+
+    if let Some(host) = hosts
+        .iter()
+        .find(|host| host.contains(|c: char| c.is_whitespace() || c == '/'))
+    {
+        return reply(ctx, format!("`{host}` is not a valid host.")).await;
+    }
+
+    let rule = sqlx::query!(
+        r#"
+            SELECT
+                id AS "id!"
+            FROM embed_rules
+            WHERE name = $1;
+        "#,
+        name,
+    )
+    .fetch_optional(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, name = %name, "an error occurred when fetching embed rule"),
+    )?;
+
+    let Some(rule) = rule else {
+        return reply(ctx, format!("there is no rule named \"{name}\".")).await;
+    };
+
+    let mut tx = ctx.data().db.begin().await?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM embed_rule_hosts
+            WHERE rule_id = $1;
+        "#,
+        rule.id,
+    )
+    .execute(&mut *tx)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, name = %name, "an error occurred when clearing embed rule hosts"),
+    )?;
+
+    for (position, host) in hosts.iter().enumerate() {
+        let position = position as i64;
+
+        sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO
+                    embed_rule_hosts (rule_id, host, position)
+                VALUES
+                    ($1, $2, $3);
+            "#,
+            rule.id,
+            host,
+            position,
+        )
+        .execute(&mut *tx)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, name = %name, host = %host, "an error occurred when adding embed rule host"),
+        )?;
+    }
+
+    tx.commit().await?;
+
+    reload_embed_rules(ctx.data()).await?;
+    check_fixer_hosts(ctx.data()).await;
+
+    if hosts.is_empty() {
+        return reply(ctx, format!("cleared the fixer hosts of \"{name}\".")).await;
+    }
+
+    reply(
+        ctx,
+        format!(
+            "\"{}\" will now use the first healthy host of {}.",
+            name,
+            hosts
+                .iter()
+                .map(|host| format!("`{host}`"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    )
+    .await
+}
+
+/// show how a message would be rewritten by the current rules, in this channel.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command)]
+pub async fn test(
+    ctx: Context<'_>,
+    #[description = "text containing the links to test."]
+    #[rest]
+    text: String,
+) -> Result<(), Error> {
+    let text = strip_code(&text);
+
+    let disabled_rules = match ctx.guild_id() {
+        Some(guild_id) => {
+            let controls = get_embed_controls(ctx.data(), guild_id).await?;
+
+            if !controls.is_channel_enabled(ctx.channel_id()) {
+                return reply(ctx, "links aren't rewritten in this channel.".to_string()).await;
+            }
+
+            controls.disabled_rules
+        }
+        None => vec![],
+    };
+
+    let results = {
+        let health = ctx.data().fixer_health.read().unwrap();
+
+        ctx.data()
+            .embed_rules
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|rule| !disabled_rules.contains(&rule.name))
+            .filter_map(|rule| {
+                if !rule.regex.is_match(text).unwrap_or(false) {
+                    return None;
+                }
+
+                let urls = rule.rewrite_all(text, |host| is_host_healthy(&health, host));
+
+                Some(if urls.is_empty() {
+                    format!("**{}** → all fixer hosts are down", rule.name)
+                } else {
+                    urls.iter()
+                        .map(|(_, url)| format!("**{}** → <{}>", rule.name, url))
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                })
+            })
+            .collect::<Vec<String>>()
+    };
+
+    if results.is_empty() {
+        return reply(ctx, "no enabled rule matches that.".to_string()).await;
+    }
+
+    reply(ctx, results.join("\n")).await
+}
+
+/// set whether a rule is turned off in this server.
+async fn set_rule_disabled(ctx: Context<'_>, name: &str, disabled: bool) -> Result<(), Error> {
+    let guild = ctx.guild_id().unwrap();
+    let guild_id = guild.to_string();
+
+    let rule = sqlx::query!(
+        r#"
+            SELECT
+                id AS "id!"
+            FROM embed_rules
+            WHERE name = $1;
+        "#,
+        name,
+    )
+    .fetch_optional(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, name = %name, "an error occurred when fetching embed rule"),
+    )?;
+
+    let Some(rule) = rule else {
+        return reply(ctx, format!("there is no rule named \"{name}\".")).await;
+    };
+
+    if disabled {
+        sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO
+                    disabled_embed_rules (guild_id, rule_id)
+                VALUES
+                    ($1, $2);
+            "#,
+            guild_id,
+            rule.id,
+        )
+        .execute(&ctx.data().db)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, name = %name, guild_id = %guild_id, "an error occurred when disabling embed rule"),
+        )?;
+
+        forget_embed_controls(ctx.data(), guild);
+
+        reply(
+            ctx,
+            format!("disabled embed rule \"{name}\" in this server."),
+        )
+        .await
+    } else {
+        sqlx::query!(
+            r#"
+                DELETE FROM disabled_embed_rules
+                WHERE guild_id = $1 AND rule_id = $2;
+            "#,
+            guild_id,
+            rule.id,
+        )
+        .execute(&ctx.data().db)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, name = %name, guild_id = %guild_id, "an error occurred when enabling embed rule"),
+        )?;
+
+        forget_embed_controls(ctx.data(), guild);
+
+        reply(
+            ctx,
+            format!("enabled embed rule \"{name}\" in this server."),
+        )
+        .await
+    }
+}
+
+/// turn a rule back on in this server.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn enable(
+    ctx: Context<'_>,
+    #[description = "name of the rule."] name: String,
+) -> Result<(), Error> {
+    set_rule_disabled(ctx, &name, false).await
+}
+
+/// turn a rule off in this server, e.g. to keep raw pixiv links.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn disable(
+    ctx: Context<'_>,
+    #[description = "name of the rule."] name: String,
+) -> Result<(), Error> {
+    set_rule_disabled(ctx, &name, true).await
+}
+
+/// choose the channels links are rewritten in. once any channel is allowed, only allowed
+/// channels are rewritten in, and denied channels never are.
+#[tracing::instrument(skip_all)]
+#[poise::command(
+    prefix_command,
+    subcommand_required,
+    guild_only,
+    aliases("channels"),
+    subcommands("allow", "deny", "reset", "channel_list")
+)]
+pub async fn channel(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// whether a channel belongs to the server the command was used in.
+async fn is_guild_channel(ctx: Context<'_>, channel_id: ChannelId) -> bool {
+    channel_id
+        .to_channel(ctx)
+        .await
+        .ok()
+        .and_then(|channel| channel.guild())
+        .is_some_and(|channel| Some(channel.guild_id) == ctx.guild_id())
+}
+
+async fn set_channel(ctx: Context<'_>, channel_id: ChannelId, allowed: bool) -> Result<(), Error> {
+    let guild = ctx.guild_id().unwrap();
+    let guild_id = guild.to_string();
+    let channel = channel_id.to_string();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO
+                embed_channels (guild_id, channel_id, allowed)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (guild_id, channel_id)
+            DO UPDATE SET
+                allowed = excluded.allowed;
+        "#,
+        guild_id,
+        channel,
+        allowed,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, channel_id = %channel_id, "an error occurred when updating embed channel"),
+    )?;
+
+    forget_embed_controls(ctx.data(), guild);
+
+    Ok(())
+}
+
+/// allow links to be rewritten in a channel. defaults to the current one.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn allow(
+    ctx: Context<'_>,
+    #[description = "the channel to allow."] channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    let channel_id = channel.unwrap_or(ctx.channel_id());
+
+    if !is_guild_channel(ctx, channel_id).await {
+        return reply(ctx, "that channel isn't in this server.".to_string()).await;
+    }
+
+    set_channel(ctx, channel_id, true).await?;
+
+    reply(
+        ctx,
+        format!("links will be rewritten in {}.", channel_id.mention()),
+    )
+    .await
+}
+
+/// stop links from being rewritten in a channel. defaults to the current one.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn deny(
+    ctx: Context<'_>,
+    #[description = "the channel to deny."] channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    let channel_id = channel.unwrap_or(ctx.channel_id());
+
+    if !is_guild_channel(ctx, channel_id).await {
+        return reply(ctx, "that channel isn't in this server.".to_string()).await;
+    }
+
+    set_channel(ctx, channel_id, false).await?;
+
+    reply(
+        ctx,
+        format!(
+            "links won't be rewritten in {} anymore.",
+            channel_id.mention()
+        ),
+    )
+    .await
+}
+
+/// take a channel off the allow and deny lists. defaults to the current one.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn reset(
+    ctx: Context<'_>,
+    #[description = "the channel to reset."] channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    let channel_id = channel.unwrap_or(ctx.channel_id());
+
+    if !is_guild_channel(ctx, channel_id).await {
+        return reply(ctx, "that channel isn't in this server.".to_string()).await;
+    }
+    let guild = ctx.guild_id().unwrap();
+    let guild_id = guild.to_string();
+    let channel = channel_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM embed_channels
+            WHERE guild_id = $1 AND channel_id = $2;
+        "#,
+        guild_id,
+        channel,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, channel_id = %channel_id, "an error occurred when resetting embed channel"),
+    )?;
+
+    forget_embed_controls(ctx.data(), guild);
+
+    if result.rows_affected() == 0 {
+        return reply(
+            ctx,
+            format!("{} isn't on either list.", channel_id.mention()),
+        )
+        .await;
+    }
+
+    reply(
+        ctx,
+        format!("{} is off the lists now.", channel_id.mention()),
+    )
+    .await
+}
+
+/// list the channels on the allow and deny lists.
+#[tracing::instrument(skip_all)]
+#[poise::command(prefix_command, guild_only, rename = "list")]
+pub async fn channel_list(ctx: Context<'_>) -> Result<(), Error> {
+    let controls = get_embed_controls(ctx.data(), ctx.guild_id().unwrap()).await?;
+
+    let mentions = |channels: &[ChannelId]| {
+        if channels.is_empty() {
+            "none".to_string()
+        } else {
+            channels
+                .iter()
+                .map(|channel_id| channel_id.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        }
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .embed(
+                CreateEmbed::default()
+                    .title("embed channels")
+                    .field("allowed", mentions(&controls.allowed), false)
+                    .field("denied", mentions(&controls.denied), false),
+            ),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// stop the bot from rewriting your links, in every server.
+#[tracing::instrument(skip_all)]
+#[poise::command(prefix_command)]
+pub async fn optout(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+
+    sqlx::query!(
+        r#"
+            INSERT OR IGNORE INTO
+                embed_optouts (user_id)
+            VALUES
+                ($1);
+        "#,
+        user_id,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, user_id = %user_id, "an error occurred when opting out of embeds"),
+    )?;
+
+    ctx.data()
+        .embed_optouts
+        .write()
+        .unwrap()
+        .insert(ctx.author().id);
+
+    reply(
+        ctx,
+        "i won't rewrite your links anymore. use `s>embed optin` to undo this.".to_string(),
+    )
+    .await
+}
+
+/// let the bot rewrite your links again.
+#[tracing::instrument(skip_all)]
+#[poise::command(prefix_command)]
+pub async fn optin(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+
+    sqlx::query!(
+        r#"
+            DELETE FROM embed_optouts
+            WHERE user_id = $1;
+        "#,
+        user_id,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, user_id = %user_id, "an error occurred when opting in to embeds"),
+    )?;
+
+    ctx.data()
+        .embed_optouts
+        .write()
+        .unwrap()
+        .remove(&ctx.author().id);
+
+    reply(ctx, "i'll rewrite your links again.".to_string()).await
+}
@@ -1,11 +1,16 @@
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
-use mangadex_api_types_rust::MangaFeedSortOrder;
 use poise::serenity_prelude::*;
 
-use crate::constants::manga::{MD_BLOCKED_LIST, MD_URL_REGEX};
-use crate::models::manga::Manga;
-use crate::{Context, Error};
+use crate::chapter_tracker::cache_manga_tags;
+use crate::constants::manga::{MD_TRACKED_LANGUAGE, MD_URL_REGEX};
+use crate::mangadex::{LiveMangaDex, MangaDexApi, MangaInfo, manga_info};
+use crate::models::manga::{ExportedManga, Manga, TrackerSource, TrackingListExport};
+use crate::paginator::{self, Page, PageSource};
+use crate::{Context, Data, Error};
+
+const MAX_EMBED_FIELD_LENGTH: usize = 1024;
 
 struct InternalManga {
     title: String,
@@ -13,9 +18,15 @@ struct InternalManga {
     last_updated: Option<time::OffsetDateTime>,
 }
 
-/// check mangadex client's availability.
-async fn check_md_client(ctx: Context<'_>) -> Result<(), Error> {
-    if ctx.data().md.is_none() {
+struct ResolvedManga {
+    id: uuid::fmt::Hyphenated,
+    title: Option<String>,
+    tracked: bool,
+}
+
+/// check mangadex client's availability, returning the api to make calls with.
+async fn check_md_client(ctx: Context<'_>) -> Result<LiveMangaDex<'_>, Error> {
+    let Some(api) = ctx.data().mangadex() else {
         ctx.send(
             poise::CreateReply::default()
                 .reply(true)
@@ -26,7 +37,7 @@ async fn check_md_client(ctx: Context<'_>) -> Result<(), Error> {
         .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
 
         return Err("mangadex client is not initialized.".into());
-    }
+    };
 
     if ctx.data().mdlist_id.is_none() {
         ctx.send(
@@ -41,16 +52,428 @@ async fn check_md_client(ctx: Context<'_>) -> Result<(), Error> {
         return Err("mdlist uuid is not set.".into());
     }
 
+    Ok(api)
+}
+
+/// replace the contents of the mdlist with the titles in the database.
+async fn update_mdlist(data: &Data, api: &dyn MangaDexApi) -> Result<(), Error> {
+    let Some(mdlist_id) = data.mdlist_id else {
+        return Err("mdlist uuid is not set.".into());
+    };
+
+    let manga_list = sqlx::query_scalar!(
+        r#"
+            SELECT
+                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated"
+            FROM manga;
+        "#
+    )
+    .fetch_all(&data.db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    )?;
+
+    api.replace_custom_list(
+        mdlist_id,
+        manga_list.into_iter().map(|id| id.into_uuid()).collect(),
+    )
+    .await
+    .inspect_err(|e| tracing::warn!(err = ?e, "an error occurred when updating the mdlist"))?;
+
     Ok(())
 }
 
+/// follows or unfollows a manga on the logged-in mangadex account, so that the follows feed
+/// tracker picks it up.
+async fn set_followed(api: &dyn MangaDexApi, uuid: uuid::Uuid, follow: bool) -> Result<(), Error> {
+    api.set_followed(uuid, follow).await.inspect_err(
+        |e| tracing::warn!(err = ?e, uuid = %uuid, follow, "an error occurred when updating manga follow"),
+    )
+}
+
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// how many single-character edits it takes to turn `a` into `b`.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// whether `query` is the start of `title` with a few typos, one for every 5 characters.
+fn is_typo_prefix(query: &str, title: &str) -> bool {
+    let query = query.chars().collect::<Vec<char>>();
+    let title = title.chars().collect::<Vec<char>>();
+    let allowed = query.len() / 5;
+
+    // the typos may have added or dropped characters, so try the neighbouring prefix lengths too.
+    allowed > 0
+        && (query.len().saturating_sub(allowed)..=query.len() + allowed)
+            .filter(|len| *len <= title.len())
+            .any(|len| edit_distance(&query, &title[..len]) <= allowed)
+}
+
+/// score how closely `title` matches `query`, with higher being better. returns `None` if it
+/// doesn't match at all.
+fn fuzzy_score(query: &str, title: &str) -> Option<u8> {
+    let query = normalize_title(query);
+    let title = normalize_title(title);
+
+    if query.is_empty() {
+        None
+    } else if title == query {
+        Some(5)
+    } else if title.starts_with(&query) {
+        Some(4)
+    } else if title.contains(&query) {
+        Some(3)
+    } else if query.split(' ').all(|word| title.contains(word)) {
+        Some(2)
+    } else if is_typo_prefix(&query, &title) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// the titles matching `query` best. more than one means they matched equally well.
+fn best_matches<T>(query: &str, titles: impl IntoIterator<Item = (T, String)>) -> Vec<(T, String)> {
+    let mut candidates = titles
+        .into_iter()
+        .filter_map(|(id, title)| Some((fuzzy_score(query, &title)?, id, title)))
+        .collect::<Vec<_>>();
+
+    let best_score = candidates.iter().map(|(score, _, _)| *score).max();
+    candidates.retain(|(score, _, _)| Some(*score) == best_score);
+
+    candidates
+        .into_iter()
+        .map(|(_, id, title)| (id, title))
+        .collect()
+}
+
+/// publish date of the newest english chapter of a manga, used as the starting point for tracking.
+async fn latest_chapter_date(
+    api: &dyn MangaDexApi,
+    uuid: uuid::Uuid,
+) -> Result<Option<time::OffsetDateTime>, Error> {
+    let chapter = api.latest_chapter(uuid, None).await.inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching chapter feed"),
+    )?;
+
+    Ok(chapter.and_then(|chapter| chapter.publish_at))
+}
+
+/// insert a manga into the tracking list and mirror it to mangadex.
+///
+/// returns warnings to show the user when mirroring to mangadex failed, or `None` when the manga
+/// was already tracked.
+async fn insert_tracked_manga(
+    data: &Data,
+    api: &dyn MangaDexApi,
+    manga: &MangaInfo,
+    latest_chapter_date: Option<time::OffsetDateTime>,
+) -> Result<Option<String>, Error> {
+    let now = time::OffsetDateTime::now_utc();
+    let last_updated = time::OffsetDateTime::new_utc(now.date(), now.time());
+    let uuid = manga.id;
+    let hyphenated = uuid.hyphenated();
+
+    // a double click on a track button can get here twice.
+    let inserted = sqlx::query!(
+        r#"
+            INSERT INTO
+                manga (manga_dex_id, last_chapter_date, last_updated, title, status)
+            VALUES
+                ($1, $2, $3, $4, $5)
+            ON CONFLICT (manga_dex_id)
+            DO NOTHING;
+        "#,
+        hyphenated,
+        latest_chapter_date,
+        last_updated,
+        manga.title,
+        manga.status,
+    )
+    .execute(&data.db)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    cache_manga_tags(&data.db, manga).await.inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when caching manga tags"),
+    )?;
+
+    let mut resp_string = String::new();
+
+    let _ = update_mdlist(data, api).await.map_err(|_| {
+        resp_string = "*failed to update the mdlist. it will (hopefully) be updated the next time \
+                       you add a manga. you can also try running `s>manga sync` to sync the \
+                       mdlist.*\n\n"
+            .to_string()
+    });
+
+    if data.tracker_source == TrackerSource::Follows && set_followed(api, uuid, true).await.is_err()
+    {
+        resp_string += "*failed to follow the manga on mangadex. new chapters will not be \
+                        announced until it is followed.*\n\n";
+    }
+
+    Ok(Some(resp_string))
+}
+
+/// the tracked manga with the nickname `alias`, ignoring case.
+async fn find_alias(
+    db: &sqlx::Pool<sqlx::Sqlite>,
+    alias: &str,
+) -> Result<Option<ResolvedManga>, Error> {
+    let manga = sqlx::query!(
+        r#"
+            SELECT
+                m.manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
+                m.title
+            FROM
+                manga_aliases ma
+            INNER JOIN
+                manga m ON ma.manga_dex_id = m.manga_dex_id
+            WHERE
+                ma.alias = $1;
+        "#,
+        alias
+    )
+    .fetch_optional(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, alias = %alias, "an error occurred when fetching manga alias"),
+    )?;
+
+    Ok(manga.map(|manga| ResolvedManga {
+        id: manga.manga_dex_id,
+        title: manga.title,
+        tracked: true,
+    }))
+}
+
+/// resolve user input into a manga.
+///
+/// links and uuids are used as-is, then nicknames are checked, and finally the input is fuzzy
+/// matched against the cached titles of tracked manga. if several titles match equally well, the
+/// user is asked to pick one. returns `None` after replying to the user if nothing was resolved.
+async fn resolve_manga(ctx: Context<'_>, input: &str) -> Result<Option<ResolvedManga>, Error> {
+    let input = input.trim();
+
+    let tracked_list = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
+                title
+            FROM manga;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    )?;
+
+    let uuid = match MD_URL_REGEX.captures(input) {
+        Ok(Some(captures)) => uuid::Uuid::try_parse(&captures[1]).ok(),
+        _ => uuid::Uuid::try_parse(input).ok(),
+    };
+
+    if let Some(uuid) = uuid {
+        let uuid = uuid.hyphenated();
+        let tracked = tracked_list.iter().find(|manga| manga.manga_dex_id == uuid);
+
+        return Ok(Some(ResolvedManga {
+            id: uuid,
+            title: tracked.and_then(|manga| manga.title.clone()),
+            tracked: tracked.is_some(),
+        }));
+    }
+
+    if let Some(manga) = find_alias(&ctx.data().db, input).await? {
+        return Ok(Some(manga));
+    }
+
+    let mut candidates = best_matches(
+        input,
+        tracked_list
+            .into_iter()
+            .filter_map(|manga| Some((manga.manga_dex_id, manga.title?))),
+    );
+
+    match candidates.len() {
+        0 => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .reply(true)
+                    .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                    .content(format!(
+                        "couldn't find a tracked manga matching \"{}\".",
+                        input
+                    )),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+            Ok(None)
+        }
+        1 => {
+            let (id, title) = candidates.remove(0);
+
+            Ok(Some(ResolvedManga {
+                id,
+                title: Some(title),
+                tracked: true,
+            }))
+        }
+        _ => {
+            let select_id = format!("{}pick", ctx.id());
+
+            let options = candidates
+                .iter()
+                .take(25)
+                .map(|(id, title)| {
+                    CreateSelectMenuOption::new(
+                        title.chars().take(100).collect::<String>(),
+                        id.to_string(),
+                    )
+                })
+                .collect();
+
+            let msg = ctx
+                .send(
+                    poise::CreateReply::default()
+                        .reply(true)
+                        .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                        .content(format!(
+                            "found {} titles matching \"{}\". which one did you mean?",
+                            candidates.len(),
+                            input
+                        ))
+                        .components(vec![CreateActionRow::SelectMenu(
+                            CreateSelectMenu::new(
+                                &select_id,
+                                CreateSelectMenuKind::String { options },
+                            )
+                            .placeholder("pick a title"),
+                        )]),
+                )
+                .await
+                .inspect_err(
+                    |e| tracing::error!(err = ?e, "an error occurred when sending reply"),
+                )?;
+
+            let press = collector::ComponentInteractionCollector::new(ctx)
+                .author_id(ctx.author().id)
+                .custom_ids(vec![select_id])
+                .timeout(std::time::Duration::from_secs(60))
+                .await;
+
+            let picked = press.as_ref().and_then(|press| match &press.data.kind {
+                ComponentInteractionDataKind::StringSelect { values } => candidates
+                    .iter()
+                    .find(|(id, _)| values.first() == Some(&id.to_string())),
+                _ => None,
+            });
+
+            match (press.as_ref(), picked) {
+                (Some(press), Some((id, title))) => {
+                    press
+                        .create_response(
+                            ctx,
+                            CreateInteractionResponse::UpdateMessage(
+                                CreateInteractionResponseMessage::new()
+                                    .content(format!("picked **{}**.", title))
+                                    .components(vec![]),
+                            ),
+                        )
+                        .await
+                        .inspect_err(
+                            |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+                        )?;
+
+                    Ok(Some(ResolvedManga {
+                        id: *id,
+                        title: Some(title.clone()),
+                        tracked: true,
+                    }))
+                }
+                _ => {
+                    msg.edit(
+                        ctx,
+                        poise::CreateReply::default()
+                            .reply(true)
+                            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                            .content("no title was picked.")
+                            .components(vec![]),
+                    )
+                    .await
+                    .inspect_err(
+                        |e| tracing::error!(err = ?e, "an error occurred when editing message"),
+                    )?;
+
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+/// reply that the resolved manga isn't in the tracking list. returns `true` if it is tracked.
+async fn ensure_tracked(ctx: Context<'_>, manga: &ResolvedManga) -> Result<bool, Error> {
+    if manga.tracked {
+        return Ok(true);
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(format!(
+                "[this manga](https://mangadex.org/title/{}) is not in the tracking list.",
+                manga.id
+            )),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(false)
+}
+
 /// commands related to manga tracking.
 #[tracing::instrument(skip_all)]
 #[poise::command(
     prefix_command,
     subcommand_required,
     guild_only,
-    subcommands("add", "list", "sync")
+    subcommands(
+        "add", "list", "sync", "alias", "unalias", "stats", "random", "export", "import"
+    )
 )]
 pub async fn manga(_: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -63,17 +486,11 @@ pub async fn add(
     ctx: Context<'_>,
     #[description = "mangadex uuid or link of the manga you want to add."] input: String,
 ) -> Result<(), Error> {
-    if check_md_client(ctx).await.is_err() {
+    let Ok(api) = check_md_client(ctx).await else {
         return Ok(());
-    }
+    };
 
-    ctx.data()
-        .md
-        .as_ref()
-        .unwrap()
-        .oauth()
-        .refresh()
-        .send()
+    api.refresh_token()
         .await
         .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when refreshing token"))?;
 
@@ -135,73 +552,11 @@ pub async fn add(
 
     let uuid = uuid.hyphenated();
 
-    let manga_list = sqlx::query_as!(
-        Manga,
-        r#"
-            SELECT
-                id,
-                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
-                last_updated,
-                last_chapter_date
-            FROM manga;
-        "#
-    )
-    .fetch_all(&ctx.data().db)
-    .await
-    .inspect_err(
-        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    let manga_id = *uuid.as_uuid();
+    let manga = api.manga_attributes(manga_id).await.inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga"),
     )?;
 
-    let mdlist = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .custom_list()
-        .id(ctx.data().mdlist_id.unwrap())
-        .get()
-        .send()
-        .await
-        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching mdlist"))?;
-
-    let manga = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .manga()
-        .id(*uuid.as_uuid())
-        .get()
-        .send()
-        .await
-        .inspect_err(
-            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga"),
-        )?;
-    let manga_id = manga.data.id;
-
-    let chapter_feed = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .manga()
-        .id(*uuid.as_uuid())
-        .feed()
-        .get()
-        .add_translated_language(&mangadex_api_types_rust::Language::English)
-        .order(MangaFeedSortOrder::Chapter(
-            mangadex_api_types_rust::OrderDirection::Descending,
-        ))
-        .excluded_groups(MD_BLOCKED_LIST.clone())
-        .limit(1u32)
-        .send()
-        .await
-        .inspect_err(
-            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching chapter feed"),
-        )?;
-
-    let manga = manga.data.attributes;
-
     let title = match manga.title.get(&mangadex_api_types_rust::Language::English) {
         Some(en_title) => en_title,
         None => {
@@ -228,7 +583,9 @@ pub async fn add(
                 id AS "id!",
                 manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
                 last_updated,
-                last_chapter_date
+                last_chapter_date,
+                title,
+                status
             FROM manga
             WHERE manga_dex_id = $1;
         "#,
@@ -263,68 +620,31 @@ pub async fn add(
         .collect::<Vec<String>>()
         .join(", ");
 
-    let statistics = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .statistics()
-        .manga()
-        .id(*uuid.as_uuid())
-        .get()
-        .send()
-        .await
-        .inspect_err(
-            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga stats"),
-        )?;
-
-    let statistics = statistics.statistics.get(uuid.as_uuid()).unwrap();
-
-    let latest_chapter_date = if chapter_feed.result == mangadex_api_types_rust::ResultType::Ok
-        && !chapter_feed.data.is_empty()
-    {
-        let chapter = chapter_feed.data.first().unwrap();
-
-        let chapter_data = &chapter.attributes;
-
-        chapter_data.publish_at.map(|timestamp| {
-            time::OffsetDateTime::new_utc(timestamp.as_ref().date(), timestamp.as_ref().time())
-        })
-    } else {
-        None
-    };
+    let statistics = api.statistics(manga_id).await.inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga stats"),
+    )?;
 
-    let now = time::OffsetDateTime::now_utc();
-    let last_updated = time::OffsetDateTime::new_utc(now.date(), now.time());
+    let latest_chapter_date = latest_chapter_date(&api, manga_id).await?;
 
-    sqlx::query!(
-        r#"
-            INSERT INTO
-                manga (manga_dex_id, last_chapter_date, last_updated)
-            VALUES
-                ($1, $2, $3);
-        "#,
-        uuid,
+    let Some(resp_string) = insert_tracked_manga(
+        ctx.data(),
+        &api,
+        &manga_info(manga_id, &manga),
         latest_chapter_date,
-        last_updated,
     )
-    .execute(&ctx.data().db)
-    .await?;
-
-    let mut builder = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .custom_list()
-        .id(ctx.data().mdlist_id.unwrap())
-        .put();
-
-    for manga in manga_list {
-        builder.add_manga_id(manga.manga_dex_id.into_uuid());
-    }
+    .await?
+    else {
+        ctx.send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content(format!("**{}** is already in the tracking list.", title)),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
 
-    let mut resp_string = String::new();
+        return Ok(());
+    };
 
     ctx.channel_id()
         .edit_message(
@@ -334,21 +654,6 @@ pub async fn add(
         )
         .await?;
 
-    let _ = builder
-        .add_manga_id(uuid.as_uuid())
-        .version(mdlist.data.attributes.version)
-        .build()
-        .unwrap()
-        .send()
-        .await
-        .map_err(|e| {
-            tracing::warn!(err = ?e, "an error occurred when updating the mdlist");
-            resp_string = "*failed to update the mdlist. it will (hopefully) be updated the next \
-                           time you add a manga. you can also try running `s>manga sync` to sync \
-                           the mdlist.*\n\n"
-                .to_string()
-        });
-
     ctx.send(
         poise::CreateReply::default()
             .reply(true)
@@ -397,7 +702,7 @@ pub async fn add(
                     )
                     .field(
                         "rating",
-                        match statistics.rating.bayesian {
+                        match statistics.rating {
                             Some(avg) => avg.to_string(),
                             None => "unknown".to_string(),
                         },
@@ -425,9 +730,9 @@ pub async fn add(
 #[tracing::instrument(skip_all)]
 #[poise::command(prefix_command)]
 pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
-    if check_md_client(ctx).await.is_err() {
+    let Ok(api) = check_md_client(ctx).await else {
         return Ok(());
-    }
+    };
 
     let msg = ctx
         .send(
@@ -446,7 +751,9 @@ pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
                 id,
                 manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
                 last_updated,
-                last_chapter_date
+                last_chapter_date,
+                title,
+                status
             FROM manga;
         "#
     )
@@ -470,53 +777,85 @@ pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
         return Ok(());
     }
 
-    let mut result_list: Vec<InternalManga> = vec![];
-
-    for db_manga in manga_list {
-        let manga_id = db_manga.manga_dex_id;
+    // titles are normally cached by the tracker, but older rows might not have one yet.
+    for db_manga in manga_list.iter().filter(|m| m.title.is_none()) {
+        let manga = api.manga(db_manga.manga_dex_id.into()).await.inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %db_manga.manga_dex_id, "an error occurred when fetching manga"),
+        )?;
+        let title = manga.title;
+
+        sqlx::query!(
+            r#"
+                UPDATE manga
+                SET title = $1
+                WHERE manga_dex_id = $2;
+            "#,
+            title,
+            db_manga.manga_dex_id,
+        )
+        .execute(&ctx.data().db)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %db_manga.manga_dex_id, "an error occurred when updating manga title"),
+        )?;
+    }
 
-        let manga = ctx
-            .data()
-            .md
-            .as_ref()
-            .unwrap()
-            .manga()
-            .id(db_manga.manga_dex_id.into())
-            .get()
-            .send()
-            .await
-            .inspect_err(
-                |e| tracing::error!(err = ?e, uuid = %db_manga.manga_dex_id, "an error occurred when fetching manga"),
-            )?;
+    let pages = manga_list_pages(ctx.data()).await?;
 
-        let manga = manga.data.attributes;
+    msg.edit(
+        ctx,
+        paginator::reply(&MANGA_LIST, ctx.author().id, &pages)
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content("here's your manga list!"),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
 
-        let title = match manga.title.get(&mangadex_api_types_rust::Language::English) {
-            Some(en_title) => en_title,
-            None => {
-                match manga
-                    .title
-                    .get(&mangadex_api_types_rust::Language::JapaneseRomanized)
-                {
-                    Some(jp_ro) => jp_ro,
-                    None => manga
-                        .title
-                        .get(&mangadex_api_types_rust::Language::Japanese)
-                        .unwrap(),
-                }
-            }
-        };
+    Ok(())
+}
 
-        result_list.push(InternalManga {
-            title: title.to_string(),
-            id: manga_id.into(),
-            last_updated: db_manga.last_chapter_date,
-        });
-    }
+/// the tracked manga list, paginated from the database.
+pub const MANGA_LIST: PageSource = PageSource {
+    kind: "manga",
+    pages: |data| Box::pin(manga_list_pages(data)),
+};
 
-    result_list.sort_by(|a, b| {
-        if a.last_updated.is_none() {
-            return Ordering::Greater;
+/// builds the pages of the tracked manga list from the cached titles in the database.
+async fn manga_list_pages(data: &Data) -> Result<Vec<Page>, Error> {
+    let manga_list = sqlx::query_as!(
+        Manga,
+        r#"
+            SELECT
+                id,
+                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
+                last_updated,
+                last_chapter_date,
+                title,
+                status
+            FROM manga;
+        "#
+    )
+    .fetch_all(&data.db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    )?;
+
+    let mut result_list = manga_list
+        .into_iter()
+        .map(|db_manga| InternalManga {
+            title: db_manga
+                .title
+                .unwrap_or_else(|| db_manga.manga_dex_id.to_string()),
+            id: db_manga.manga_dex_id.into(),
+            last_updated: db_manga.last_chapter_date,
+        })
+        .collect::<Vec<InternalManga>>();
+
+    result_list.sort_by(|a, b| {
+        if a.last_updated.is_none() {
+            return Ordering::Greater;
         }
 
         if b.last_updated.is_none() {
@@ -526,8 +865,7 @@ pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
         b.last_updated.unwrap().cmp(&a.last_updated.unwrap())
     });
 
-    let mut pages: Vec<String> = vec![];
-    let mut current_page: usize = 0;
+    let mut pages: Vec<Page> = vec![];
 
     for (page, chunk) in result_list.chunks(10).enumerate() {
         let mut manga_list_str = String::new();
@@ -555,154 +893,34 @@ pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
             manga_list_str = manga_list_str + &entry_str;
         }
 
-        pages.push(manga_list_str);
-    }
-
-    let ctx_id = ctx.id();
-    let author_id = ctx.author().id;
-    let first_id = format!("{}first", ctx_id);
-    let last_id = format!("{}last", ctx_id);
-    let prev_id = format!("{}prev", ctx_id);
-    let next_id = format!("{}next", ctx_id);
-
-    msg.edit(
-        ctx,
-        poise::CreateReply::default()
-            .reply(true)
-            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-            .content("here's your manga list!")
-            .embed(
-                CreateEmbed::default()
-                    .title("list of tracked manga titles")
-                    .url(format!(
-                        "https://mangadex.org/list/{}",
-                        ctx.data().mdlist_id.unwrap()
-                    ))
-                    .description(pages[0].clone())
-                    .footer(CreateEmbedFooter::new(format!(
-                        "page {}/{}",
-                        current_page + 1,
-                        pages.len(),
-                    ))),
-            )
-            .components(vec![CreateActionRow::Buttons(vec![
-                CreateButton::new(&first_id).emoji('⏮').disabled(true),
-                CreateButton::new(&prev_id).emoji('◀').disabled(true),
-                CreateButton::new(&next_id)
-                    .emoji('▶')
-                    .disabled(current_page == pages.len() - 1),
-                CreateButton::new(&last_id)
-                    .emoji('⏭')
-                    .disabled(current_page == pages.len() - 1),
-            ])]),
-    )
-    .await
-    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-
-    while let Some(press) = collector::ComponentInteractionCollector::new(ctx)
-        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-        .timeout(std::time::Duration::from_secs(60))
-        .await
-    {
-        if press.user.id != author_id {
-            press
-                .create_response(
-                    ctx,
-                    CreateInteractionResponse::Message(
-                        CreateInteractionResponseMessage::new()
-                            .content("you cannot interact with another user's invoked command!")
-                            .ephemeral(true),
-                    ),
-                )
-                .await
-                .inspect_err(
-                    |e| tracing::error!(err = ?e, "an error occurred when creating response"),
-                )?;
-
-            continue;
-        }
+        let mut embed = CreateEmbed::default()
+            .title("list of tracked manga titles")
+            .description(manga_list_str);
 
-        if press.data.custom_id == prev_id {
-            current_page = current_page.saturating_sub(1);
-        } else if press.data.custom_id == next_id {
-            current_page += 1;
-        } else if press.data.custom_id == first_id {
-            current_page = 0;
-        } else if press.data.custom_id == last_id {
-            current_page = pages.len() - 1;
-        } else {
-            continue;
+        if let Some(mdlist_id) = data.mdlist_id {
+            embed = embed.url(format!("https://mangadex.org/list/{}", mdlist_id));
         }
 
-        press
-            .create_response(
-                ctx,
-                CreateInteractionResponse::UpdateMessage(
-                    CreateInteractionResponseMessage::new()
-                        .embed(
-                            CreateEmbed::default()
-                                .title("list of tracked manga titles")
-                                .url(format!(
-                                    "https://mangadex.org/list/{}",
-                                    ctx.data().mdlist_id.unwrap()
-                                ))
-                                .description(pages[current_page].clone())
-                                .footer(CreateEmbedFooter::new(format!(
-                                    "page {}/{}",
-                                    current_page + 1,
-                                    pages.len(),
-                                ))),
-                        )
-                        .components(vec![CreateActionRow::Buttons(vec![
-                            CreateButton::new(&first_id)
-                                .emoji('⏮')
-                                .disabled(current_page == 0),
-                            CreateButton::new(&prev_id)
-                                .emoji('◀')
-                                .disabled(current_page == 0),
-                            CreateButton::new(&next_id)
-                                .emoji('▶')
-                                .disabled(current_page == pages.len() - 1),
-                            CreateButton::new(&last_id)
-                                .emoji('⏭')
-                                .disabled(current_page == pages.len() - 1),
-                        ])]),
-                ),
-            )
-            .await
-            .inspect_err(
-                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
-            )?;
+        pages.push(Page {
+            label: chunk[0].title.clone(),
+            embed,
+        });
     }
 
-    msg.into_message()
-        .await?
-        .edit(ctx, EditMessage::default().components(vec![]))
-        .await
-        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-
-    Ok(())
+    Ok(pages)
 }
 
 /// sync the local database to the mdlist.
 #[tracing::instrument(skip_all)]
 #[poise::command(prefix_command)]
 pub async fn sync(ctx: Context<'_>) -> Result<(), Error> {
-    if check_md_client(ctx).await.is_err() {
+    let Ok(api) = check_md_client(ctx).await else {
         return Ok(());
-    }
+    };
 
-    ctx.data()
-        .md
-        .as_ref()
-        .unwrap()
-        .oauth()
-        .refresh()
-        .send()
-        .await
-        .inspect_err(
-            |e| tracing::error!(err = ?e, "an error occurred when refreshing mangadex token"),
-        )?;
+    api.refresh_token().await.inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when refreshing mangadex token"),
+    )?;
 
     let msg = ctx
         .send(
@@ -714,54 +932,7 @@ pub async fn sync(ctx: Context<'_>) -> Result<(), Error> {
         .await
         .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
 
-    let manga_list = sqlx::query_as!(
-        Manga,
-        r#"
-            SELECT
-                id,
-                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
-                last_updated,
-                last_chapter_date
-            FROM manga;
-        "#
-    )
-    .fetch_all(&ctx.data().db)
-    .await
-    .inspect_err(
-        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
-    )?;
-
-    let mdlist = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .custom_list()
-        .id(ctx.data().mdlist_id.unwrap())
-        .get()
-        .send()
-        .await?;
-
-    let mut builder = ctx
-        .data()
-        .md
-        .as_ref()
-        .unwrap()
-        .custom_list()
-        .id(ctx.data().mdlist_id.unwrap())
-        .put();
-
-    for manga in manga_list {
-        builder.add_manga_id(manga.manga_dex_id.into_uuid());
-    }
-
-    match builder
-        .version(mdlist.data.attributes.version)
-        .build()
-        .unwrap()
-        .send()
-        .await
-    {
+    match update_mdlist(ctx.data(), &api).await {
         Ok(_) => {
             msg.edit(
                 ctx,
@@ -774,8 +945,7 @@ pub async fn sync(ctx: Context<'_>) -> Result<(), Error> {
             .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
         }
 
-        Err(e) => {
-            tracing::warn!(err = ?e, "an error occurred when updating mdlist");
+        Err(_) => {
             msg.edit(
                 ctx,
                 poise::CreateReply::default()
@@ -789,3 +959,1638 @@ pub async fn sync(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// give a tracked manga a nickname that other commands will accept.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command)]
+pub async fn alias(
+    ctx: Context<'_>,
+    #[description = "mangadex uuid, link, nickname or title of the manga. use quotes if it has \
+                     spaces."]
+    title: String,
+    #[description = "the nickname to add."]
+    #[rest]
+    nickname: String,
+) -> Result<(), Error> {
+    let nickname = nickname.trim();
+
+    if nickname.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content("the nickname can't be empty."),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+        return Ok(());
+    }
+
+    let Some(manga) = resolve_manga(ctx, &title).await? else {
+        return Ok(());
+    };
+
+    if !ensure_tracked(ctx, &manga).await? {
+        return Ok(());
+    }
+
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO
+                manga_aliases (manga_dex_id, alias)
+            VALUES
+                ($1, $2);
+        "#,
+        manga.id,
+        nickname,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(|e| {
+        tracing::error!(err = ?e, uuid = %manga.id, alias = %nickname, "an error occurred when adding alias for manga");
+    });
+
+    if let Err(e) = result {
+        if !e
+            .as_database_error()
+            .is_some_and(|e| e.is_unique_violation())
+        {
+            return Err(e.into());
+        }
+
+        ctx.send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content(format!("nickname \"{nickname}\" is already taken.")),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(format!(
+                "**{}** can now be referred to as \"{}\".",
+                manga.title.as_deref().unwrap_or("the manga"),
+                nickname
+            )),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// remove a manga nickname.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command)]
+pub async fn unalias(
+    ctx: Context<'_>,
+    #[description = "the nickname to remove."]
+    #[rest]
+    nickname: String,
+) -> Result<(), Error> {
+    let nickname = nickname.trim();
+
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM manga_aliases
+            WHERE alias = $1;
+        "#,
+        nickname
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, alias = %nickname, "an error occurred when deleting alias"),
+    )?;
+
+    let content = if result.rows_affected() > 0 {
+        format!("removed nickname \"{nickname}\".")
+    } else {
+        format!("nickname \"{nickname}\" does not exist.")
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(content),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// joins lines with newlines, dropping whole lines once an embed field would overflow.
+fn join_field_lines(lines: impl Iterator<Item = String>) -> String {
+    let mut joined = String::new();
+    for line in lines {
+        let separator = usize::from(!joined.is_empty());
+        if joined.chars().count() + separator + line.chars().count() > MAX_EMBED_FIELD_LENGTH {
+            break;
+        }
+
+        if separator == 1 {
+            joined.push('\n');
+        }
+        joined.push_str(&line);
+    }
+
+    if joined.is_empty() {
+        "none".to_string()
+    } else {
+        joined
+    }
+}
+
+/// show statistics about the tracking list and its chapter history.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    const WEEKS: usize = 12;
+    const TOP: usize = 5;
+
+    let statuses = sqlx::query!(
+        r#"
+            SELECT
+                COALESCE(status, 'unknown') AS "status!: String",
+                COUNT(*) AS count
+            FROM manga
+            GROUP BY 1
+            ORDER BY count DESC;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching manga statuses"))?;
+
+    let manga_list = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id,
+                title
+            FROM manga;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    )?;
+
+    let announcements = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id,
+                published_at,
+                announced_at
+            FROM chapter_announcements
+            ORDER BY announced_at;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching chapter announcements"),
+    )?;
+
+    let groups = sqlx::query!(
+        r#"
+            SELECT
+                group_name,
+                COUNT(*) AS count
+            FROM chapter_announcement_groups
+            GROUP BY group_name
+            ORDER BY count DESC, group_name
+            LIMIT $1;
+        "#,
+        TOP as i64
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching scanlation group counts"),
+    )?;
+
+    let titles: HashMap<&str, &str> = manga_list
+        .iter()
+        .map(|m| {
+            (
+                m.manga_dex_id.as_str(),
+                m.title.as_deref().unwrap_or(m.manga_dex_id.as_str()),
+            )
+        })
+        .collect();
+
+    let total = statuses.iter().map(|s| s.count).sum::<i64>();
+    let status_str = if statuses.is_empty() {
+        "no manga tracked.".to_string()
+    } else {
+        statuses
+            .iter()
+            .map(|s| format!("{}: {}", s.status, s.count))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    // bucket announcements into weeks ending today, oldest first.
+    let now = time::OffsetDateTime::now_utc();
+    let window_start = now - time::Duration::weeks(WEEKS as i64);
+    let mut weekly = [0usize; WEEKS];
+    let mut activity: HashMap<&str, usize> = titles.keys().map(|id| (*id, 0)).collect();
+
+    for announcement in &announcements {
+        if announcement.announced_at <= window_start {
+            continue;
+        }
+
+        let weeks_ago = ((now - announcement.announced_at).whole_weeks() as usize).min(WEEKS - 1);
+        weekly[WEEKS - 1 - weeks_ago] += 1;
+
+        if let Some(count) = activity.get_mut(announcement.manga_dex_id.as_str()) {
+            *count += 1;
+        }
+    }
+
+    let max_weekly = weekly.iter().copied().max().unwrap_or(0).max(1);
+    let label_format = time::macros::format_description!("[month]/[day]");
+    let weekly_str = weekly
+        .iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let week_start = window_start + time::Duration::weeks(i as i64);
+            let bar = "█".repeat((count * 10).div_ceil(max_weekly));
+            format!(
+                "{} {:<10} {}",
+                week_start.format(&label_format).unwrap_or_default(),
+                bar,
+                count
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut activity = activity.into_iter().collect::<Vec<(&str, usize)>>();
+    activity.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| titles[a.0].cmp(titles[b.0])));
+
+    let format_activity = |entries: &[(&str, usize)]| {
+        if entries.is_empty() {
+            "none".to_string()
+        } else {
+            join_field_lines(entries.iter().map(|(id, count)| {
+                format!(
+                    "[{}](https://mangadex.org/title/{}): {} ch.",
+                    titles[id], id, count
+                )
+            }))
+        }
+    };
+
+    // split the list so a title never shows up as both most and least active.
+    let split = activity.len().min(TOP * 2).div_ceil(2);
+    let most_active = format_activity(&activity[..split]);
+    let least_active = format_activity(
+        &activity[split..]
+            .iter()
+            .rev()
+            .take(TOP)
+            .copied()
+            .collect::<Vec<(&str, usize)>>(),
+    );
+
+    // average gap between consecutive chapters, using the whole history.
+    let mut dates: HashMap<&str, Vec<time::OffsetDateTime>> = HashMap::new();
+    for announcement in &announcements {
+        dates
+            .entry(announcement.manga_dex_id.as_str())
+            .or_default()
+            .push(
+                announcement
+                    .published_at
+                    .unwrap_or(announcement.announced_at),
+            );
+    }
+
+    let mut cadence = dates
+        .into_iter()
+        .filter(|(id, dates)| dates.len() > 1 && titles.contains_key(id))
+        .map(|(id, mut dates)| {
+            dates.sort();
+            let span = *dates.last().unwrap() - *dates.first().unwrap();
+            let average = span.as_seconds_f64() / 86400.0 / (dates.len() - 1) as f64;
+            (id, average)
+        })
+        .collect::<Vec<(&str, f64)>>();
+    cadence.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+    let cadence_str = if cadence.is_empty() {
+        "not enough history yet.".to_string()
+    } else {
+        join_field_lines(cadence.iter().take(TOP * 2).map(|(id, average)| {
+            format!(
+                "[{}](https://mangadex.org/title/{}): {:.1} days",
+                titles[id], id, average
+            )
+        }))
+    };
+
+    let groups_str = if groups.is_empty() {
+        "none".to_string()
+    } else {
+        groups
+            .iter()
+            .map(|g| format!("{}: {} ch.", g.group_name, g.count))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    let embed = CreateEmbed::default()
+        .title("manga tracker statistics")
+        .description(format!(
+            "**{} tracked titles, {} chapters announced**",
+            total,
+            announcements.len()
+        ))
+        .field("by status", status_str, true)
+        .field("top scanlation groups", groups_str, true)
+        .field(
+            format!("chapters per week (last {} weeks)", WEEKS),
+            format!("```\n{}\n```", weekly_str),
+            false,
+        )
+        .field("most active", most_active, true)
+        .field("least active", least_active, true)
+        .field("average days between chapters", cadence_str, false);
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .embed(embed),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[name = "json"]
+    Json,
+    #[name = "csv"]
+    Csv,
+}
+
+/// export the tracking list, nicknames and subscriptions as a json or csv file.
+///
+/// the export includes subscriber user ids, so it is limited to the bot owners.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, owners_only)]
+pub async fn export(
+    ctx: Context<'_>,
+    #[description = "file format of the export, either json or csv. defaults to json."]
+    format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    let format = format.unwrap_or(ExportFormat::Json);
+
+    let manga_list = sqlx::query_as!(
+        Manga,
+        r#"
+            SELECT
+                id,
+                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated",
+                last_updated,
+                last_chapter_date,
+                title,
+                status
+            FROM manga;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    )?;
+
+    let aliases = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id,
+                alias
+            FROM manga_aliases
+            ORDER BY alias;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching manga aliases"))?;
+
+    let subscriptions = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id,
+                user_id
+            FROM manga_subscriptions
+            ORDER BY user_id;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching subscriptions from database"),
+    )?;
+
+    let export = TrackingListExport {
+        exported_at: time::OffsetDateTime::now_utc(),
+        manga: manga_list
+            .into_iter()
+            .map(|manga| {
+                let manga_dex_id = manga.manga_dex_id.to_string();
+
+                ExportedManga {
+                    title: manga.title,
+                    status: manga.status,
+                    last_updated: Some(manga.last_updated),
+                    last_chapter_date: manga.last_chapter_date,
+                    languages: vec![MD_TRACKED_LANGUAGE.to_string()],
+                    aliases: aliases
+                        .iter()
+                        .filter(|a| a.manga_dex_id == manga_dex_id)
+                        .map(|a| a.alias.clone())
+                        .collect(),
+                    subscribers: subscriptions
+                        .iter()
+                        .filter(|s| s.manga_dex_id == manga_dex_id)
+                        .map(|s| s.user_id.clone())
+                        .collect(),
+                    manga_dex_id,
+                }
+            })
+            .collect(),
+    };
+
+    let date = export
+        .exported_at
+        .format(time::macros::format_description!("[year][month][day]"))
+        .unwrap_or_default();
+
+    let attachment = match format {
+        ExportFormat::Json => CreateAttachment::bytes(
+            serde_json::to_vec_pretty(&export)?,
+            format!("manga-{}.json", date),
+        ),
+        ExportFormat::Csv => {
+            CreateAttachment::bytes(export.to_csv().into_bytes(), format!("manga-{}.csv", date))
+        }
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(format!(
+                "exported {} titles from the tracking list.",
+                export.manga.len()
+            ))
+            .attachment(attachment),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// lists up to 10 entries for an embed field, noting how many were left out.
+fn summarize(entries: &[String]) -> String {
+    if entries.is_empty() {
+        return "none".to_string();
+    }
+
+    let mut summary = entries
+        .iter()
+        .take(10)
+        .cloned()
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if entries.len() > 10 {
+        summary += &format!("\n*...and {} more*", entries.len() - 10);
+    }
+
+    summary
+}
+
+/// import a tracking list export, or a list of mangadex links, into the tracking list.
+///
+/// shows what would change first, and only applies the import after it is confirmed.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command)]
+pub async fn import(
+    ctx: Context<'_>,
+    #[description = "a json or csv file made by `s>manga export`."] file: Option<Attachment>,
+    #[description = "mangadex uuids or links to add, separated by spaces or new lines."]
+    #[rest]
+    links: Option<String>,
+) -> Result<(), Error> {
+    let Ok(api) = check_md_client(ctx).await else {
+        return Ok(());
+    };
+
+    let mut skipped: Vec<String> = vec![];
+
+    let entries = match (&file, &links) {
+        (Some(file), _) => {
+            let bytes = file.download().await.inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when downloading attachment"),
+            )?;
+            let text = String::from_utf8_lossy(&bytes);
+
+            let parsed = if file.filename.ends_with(".csv") || !text.trim_start().starts_with('{') {
+                TrackingListExport::from_csv(&text)
+            } else {
+                serde_json::from_str::<TrackingListExport>(&text)
+                    .map(|export| export.manga)
+                    .map_err(|e| format!("the json file is not a valid export: {}", e))
+            };
+
+            match parsed {
+                Ok(entries) => entries,
+                Err(e) => {
+                    ctx.send(
+                        poise::CreateReply::default()
+                            .reply(true)
+                            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                            .content(format!("couldn't read the import file: {}", e)),
+                    )
+                    .await
+                    .inspect_err(
+                        |e| tracing::error!(err = ?e, "an error occurred when sending reply"),
+                    )?;
+
+                    return Ok(());
+                }
+            }
+        }
+        (None, Some(links)) => links
+            .split_whitespace()
+            .map(|link| {
+                let manga_dex_id = match MD_URL_REGEX.captures(link) {
+                    Ok(Some(captures)) => captures[1].to_string(),
+                    _ => link.to_string(),
+                };
+
+                ExportedManga {
+                    manga_dex_id,
+                    title: None,
+                    status: None,
+                    last_updated: None,
+                    last_chapter_date: None,
+                    languages: vec![],
+                    aliases: vec![],
+                    subscribers: vec![],
+                }
+            })
+            .collect(),
+        (None, None) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .reply(true)
+                    .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                    .content(
+                        "attach a file made by `s>manga export`, or list some mangadex links to \
+                         import.",
+                    ),
+            )
+            .await
+            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+            return Ok(());
+        }
+    };
+
+    let tracked = sqlx::query_scalar!(
+        r#"
+            SELECT manga_dex_id
+            FROM manga;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching manga from database"),
+    )?;
+
+    let existing_aliases = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id,
+                alias
+            FROM manga_aliases
+            ORDER BY alias;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching manga aliases"))?;
+
+    let existing_subscriptions = sqlx::query!(
+        r#"
+            SELECT
+                manga_dex_id,
+                user_id
+            FROM manga_subscriptions
+            ORDER BY user_id;
+        "#
+    )
+    .fetch_all(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching subscriptions from database"),
+    )?;
+
+    let tracked: HashSet<String> = tracked.into_iter().collect();
+    let mut alias_owners: HashMap<String, String> = existing_aliases
+        .into_iter()
+        .map(|a| (a.alias.to_lowercase(), a.manga_dex_id))
+        .collect();
+    let mut subscribed: HashSet<(String, String)> = existing_subscriptions
+        .into_iter()
+        .map(|s| (s.manga_dex_id, s.user_id))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut new_manga: Vec<ExportedManga> = vec![];
+    let mut already_tracked = 0;
+    let mut new_aliases: Vec<(String, String)> = vec![];
+    let mut new_subscriptions: Vec<(String, String)> = vec![];
+    let mut other_languages = false;
+
+    for mut entry in entries {
+        let Ok(uuid) = uuid::Uuid::try_parse(entry.manga_dex_id.trim()) else {
+            skipped.push(format!(
+                "`{}`: not a mangadex uuid or link",
+                entry.manga_dex_id
+            ));
+            continue;
+        };
+
+        let manga_dex_id = uuid.hyphenated().to_string();
+
+        if !seen.insert(manga_dex_id.clone()) {
+            continue;
+        }
+
+        other_languages |= entry
+            .languages
+            .iter()
+            .any(|language| language != MD_TRACKED_LANGUAGE);
+
+        if tracked.contains(&manga_dex_id) {
+            already_tracked += 1;
+        } else {
+            if entry.title.is_none() {
+                match api.manga(uuid).await {
+                    Ok(manga) => {
+                        entry.title = manga.title;
+                        entry.status = Some(manga.status);
+                    }
+                    Err(e) => {
+                        tracing::warn!(err = ?e, uuid = %uuid, "an error occurred when fetching manga");
+                        skipped.push(format!("`{}`: not found on mangadex", manga_dex_id));
+                        continue;
+                    }
+                }
+            }
+
+            entry.manga_dex_id = manga_dex_id.clone();
+            new_manga.push(entry.clone());
+        }
+
+        for alias in &entry.aliases {
+            match alias_owners.get(&alias.to_lowercase()) {
+                Some(owner) if *owner == manga_dex_id => {}
+                Some(_) => skipped.push(format!(
+                    "nickname `{}`: already used by another manga",
+                    alias
+                )),
+                None => {
+                    alias_owners.insert(alias.to_lowercase(), manga_dex_id.clone());
+                    new_aliases.push((manga_dex_id.clone(), alias.clone()));
+                }
+            }
+        }
+
+        for user_id in &entry.subscribers {
+            if user_id.parse::<u64>().is_err() {
+                skipped.push(format!("subscriber `{}`: not a discord user id", user_id));
+                continue;
+            }
+
+            if subscribed.insert((manga_dex_id.clone(), user_id.clone())) {
+                new_subscriptions.push((manga_dex_id.clone(), user_id.clone()));
+            }
+        }
+    }
+
+    if new_manga.is_empty() && new_aliases.is_empty() && new_subscriptions.is_empty() {
+        let mut content = format!(
+            "nothing to import - {} titles are already tracked.",
+            already_tracked
+        );
+
+        if !skipped.is_empty() {
+            content += &format!("\n\nskipped:\n{}", summarize(&skipped));
+        }
+
+        ctx.send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content(content),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+        return Ok(());
+    }
+
+    let title_of = |manga_dex_id: &str| {
+        new_manga
+            .iter()
+            .find(|m| m.manga_dex_id == manga_dex_id)
+            .and_then(|m| m.title.clone())
+            .unwrap_or_else(|| manga_dex_id.to_string())
+    };
+
+    let mut embed = CreateEmbed::default()
+        .title("import preview")
+        .description(format!(
+            "**{}** new titles, **{}** already tracked. nothing has been changed yet.",
+            new_manga.len(),
+            already_tracked
+        ))
+        .field(
+            "titles to add",
+            summarize(
+                &new_manga
+                    .iter()
+                    .map(|m| {
+                        format!(
+                            "[{}](https://mangadex.org/title/{})",
+                            m.title.as_deref().unwrap_or(&m.manga_dex_id),
+                            m.manga_dex_id
+                        )
+                    })
+                    .collect::<Vec<String>>(),
+            ),
+            false,
+        )
+        .field(
+            "nicknames to add",
+            summarize(
+                &new_aliases
+                    .iter()
+                    .map(|(id, alias)| format!("`{}` → {}", alias, title_of(id)))
+                    .collect::<Vec<String>>(),
+            ),
+            false,
+        )
+        .field(
+            "subscriptions to add",
+            summarize(
+                &new_subscriptions
+                    .iter()
+                    .map(|(id, user_id)| format!("<@{}> → {}", user_id, title_of(id)))
+                    .collect::<Vec<String>>(),
+            ),
+            false,
+        );
+
+    if !skipped.is_empty() {
+        embed = embed.field("skipped", summarize(&skipped), false);
+    }
+
+    if other_languages {
+        embed = embed.footer(CreateEmbedFooter::new(
+            "per-title languages are not supported yet. only english chapters will be tracked.",
+        ));
+    }
+
+    let ctx_id = ctx.id();
+    let confirm_id = format!("{}confirm", ctx_id);
+    let cancel_id = format!("{}cancel", ctx_id);
+
+    let msg = ctx
+        .send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .embed(embed)
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new(&confirm_id)
+                        .label("import")
+                        .style(ButtonStyle::Success),
+                    CreateButton::new(&cancel_id)
+                        .label("cancel")
+                        .style(ButtonStyle::Secondary),
+                ])]),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    let press = collector::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .custom_ids(vec![confirm_id.clone(), cancel_id])
+        .timeout(std::time::Duration::from_secs(60))
+        .await;
+
+    let Some(press) = press.filter(|press| press.data.custom_id == confirm_id) else {
+        msg.edit(
+            ctx,
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content("the import was cancelled. nothing has been changed.")
+                .components(vec![]),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+        return Ok(());
+    };
+
+    press
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when creating response"))?;
+
+    let now = time::OffsetDateTime::now_utc();
+    let now = time::OffsetDateTime::new_utc(now.date(), now.time());
+    let mut tx = ctx.data().db.begin().await?;
+
+    for manga in &new_manga {
+        let last_updated = manga
+            .last_updated
+            .map(|t| t.to_offset(time::UtcOffset::UTC))
+            .unwrap_or(now);
+        let last_chapter_date = manga
+            .last_chapter_date
+            .map(|t| t.to_offset(time::UtcOffset::UTC));
+
+        sqlx::query!(
+            r#"
+                INSERT INTO
+                    manga (manga_dex_id, last_updated, last_chapter_date, title, status)
+                VALUES
+                    ($1, $2, $3, $4, $5)
+                ON CONFLICT (manga_dex_id)
+                DO NOTHING;
+            "#,
+            manga.manga_dex_id,
+            last_updated,
+            last_chapter_date,
+            manga.title,
+            manga.status,
+        )
+        .execute(&mut *tx)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %manga.manga_dex_id, "an error occurred when importing manga"),
+        )?;
+    }
+
+    for (manga_dex_id, alias) in &new_aliases {
+        sqlx::query!(
+            r#"
+                INSERT INTO
+                    manga_aliases (manga_dex_id, alias)
+                VALUES
+                    ($1, $2)
+                ON CONFLICT (alias)
+                DO NOTHING;
+            "#,
+            manga_dex_id,
+            alias,
+        )
+        .execute(&mut *tx)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %manga_dex_id, "an error occurred when importing manga alias"),
+        )?;
+    }
+
+    for (manga_dex_id, user_id) in &new_subscriptions {
+        sqlx::query!(
+            r#"
+                INSERT INTO
+                    manga_subscriptions (manga_dex_id, user_id)
+                VALUES
+                    ($1, $2)
+                ON CONFLICT (manga_dex_id, user_id)
+                DO NOTHING;
+            "#,
+            manga_dex_id,
+            user_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %manga_dex_id, "an error occurred when importing subscription"),
+        )?;
+    }
+
+    tx.commit()
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when committing import"))?;
+
+    let mut resp_string = String::new();
+
+    if !new_manga.is_empty() {
+        api.refresh_token().await.inspect_err(
+            |e| tracing::error!(err = ?e, "an error occurred when refreshing token"),
+        )?;
+
+        if update_mdlist(ctx.data(), &api).await.is_err() {
+            resp_string += "*failed to update the mdlist. you can try running `s>manga sync` to \
+                            sync the mdlist.*\n\n";
+        }
+
+        if ctx.data().tracker_source == TrackerSource::Follows {
+            let mut failed = 0;
+
+            for manga in &new_manga {
+                if let Ok(uuid) = uuid::Uuid::try_parse(&manga.manga_dex_id)
+                    && set_followed(&api, uuid, true).await.is_err()
+                {
+                    failed += 1;
+                }
+            }
+
+            if failed > 0 {
+                resp_string += &format!(
+                    "*failed to follow {} titles on mangadex. their chapters will not be \
+                     announced until they are followed.*\n\n",
+                    failed
+                );
+            }
+        }
+    }
+
+    msg.edit(
+        ctx,
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(
+                resp_string
+                    + &format!(
+                        "imported {} titles, {} nicknames and {} subscriptions!",
+                        new_manga.len(),
+                        new_aliases.len(),
+                        new_subscriptions.len()
+                    ),
+            )
+            .components(vec![]),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+    Ok(())
+}
+
+/// prefix of the reroll button's custom id, `mr:<author>:<current manga>:<filter>`.
+const RANDOM_CUSTOM_ID_PREFIX: &str = "mr";
+
+/// discord custom ids are capped at 100 characters, which leaves this much room for the filter.
+const RANDOM_FILTER_MAX_LENGTH: usize = 32;
+
+/// tracked manga whose cached status, demographic or tags match `filter`, or all of them.
+async fn random_candidates(
+    db: &sqlx::Pool<sqlx::Sqlite>,
+    filter: Option<&str>,
+) -> Result<Vec<uuid::fmt::Hyphenated>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT
+                manga_dex_id AS "manga_dex_id: uuid::fmt::Hyphenated"
+            FROM manga
+            WHERE
+                $1 IS NULL
+                OR status = $1 COLLATE NOCASE
+                OR demographic = $1
+                OR EXISTS (
+                    SELECT 1
+                    FROM manga_tags
+                    WHERE manga_tags.manga_dex_id = manga.manga_dex_id AND tag = $1
+                );
+        "#,
+        filter,
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// picks a random tracked manga matching `filter`, avoiding `exclude` when there is anything else.
+///
+/// filters are matched against the statuses, demographics and tags cached by the chapter tracker,
+/// so only the picked manga is fetched from mangadex.
+async fn pick_random_manga(
+    data: &Data,
+    api: &dyn MangaDexApi,
+    filter: Option<&str>,
+    exclude: Option<uuid::Uuid>,
+) -> Result<Option<(uuid::Uuid, mangadex_api_schema_rust::v5::MangaAttributes)>, Error> {
+    use rand::seq::SliceRandom;
+
+    let mut candidates = random_candidates(&data.db, filter)
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching manga"))?;
+
+    if candidates.len() > 1 {
+        candidates.retain(|manga_dex_id| Some(*manga_dex_id.as_uuid()) != exclude);
+    }
+
+    let Some(uuid) = candidates
+        .choose(&mut rand::thread_rng())
+        .map(|manga_dex_id| *manga_dex_id.as_uuid())
+    else {
+        return Ok(None);
+    };
+
+    let manga = api.manga_attributes(uuid).await.inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga"),
+    )?;
+
+    Ok(Some((uuid, manga)))
+}
+
+fn random_components(
+    author_id: UserId,
+    manga_id: uuid::Uuid,
+    filter: Option<&str>,
+    mut buttons: Vec<CreateButton>,
+) -> Vec<CreateActionRow> {
+    buttons.push(
+        CreateButton::new(format!(
+            "{}:{}:{}:{}",
+            RANDOM_CUSTOM_ID_PREFIX,
+            author_id,
+            manga_id,
+            filter.unwrap_or_default()
+        ))
+        .label("reroll")
+        .emoji('🎲')
+        .style(ButtonStyle::Secondary),
+    );
+
+    vec![CreateActionRow::Buttons(buttons)]
+}
+
+fn random_not_found(filter: Option<&str>) -> String {
+    match filter {
+        Some(filter) => format!("couldn't find a tracked manga matching `{}`.", filter),
+        None => "the tracking list is empty.".to_string(),
+    }
+}
+
+/// pick a random manga from the tracking list, optionally filtered by tag, status or demographic.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command)]
+pub async fn random(
+    ctx: Context<'_>,
+    #[description = "tag, status or demographic to pick from."]
+    #[rest]
+    filter: Option<String>,
+) -> Result<(), Error> {
+    let Ok(api) = check_md_client(ctx).await else {
+        return Ok(());
+    };
+
+    let filter = filter.as_deref().map(str::trim).filter(|f| !f.is_empty());
+
+    if filter.is_some_and(|filter| filter.len() > RANDOM_FILTER_MAX_LENGTH) {
+        ctx.send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content(format!(
+                    "filters can be at most {} characters long.",
+                    RANDOM_FILTER_MAX_LENGTH
+                )),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+        return Ok(());
+    }
+
+    ctx.defer().await?;
+
+    let Some((manga_id, manga)) = pick_random_manga(ctx.data(), &api, filter, None).await? else {
+        ctx.send(
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content(random_not_found(filter)),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+        return Ok(());
+    };
+
+    let preview = crate::handlers::manga_preview(
+        ctx.serenity_context(),
+        ctx.data(),
+        &api,
+        manga_id,
+        &manga,
+        ctx.channel_id(),
+        ctx.guild_id(),
+    )
+    .await?;
+
+    let mut reply = poise::CreateReply::default()
+        .reply(true)
+        .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+        .content("here's your random pick!")
+        .embed(preview.embed)
+        .components(random_components(
+            ctx.author().id,
+            manga_id,
+            filter,
+            preview.buttons,
+        ));
+
+    if let Some(cover) = preview.cover {
+        reply = reply.attachment(cover);
+    }
+
+    ctx.send(reply)
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// handles the reroll button on `s>manga random` replies.
+///
+/// interactions whose custom id does not belong to a random pick are ignored.
+pub async fn handle_random_interaction(
+    ctx: &poise::serenity_prelude::Context,
+    data: &Data,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    let Interaction::Component(press) = interaction else {
+        return Ok(());
+    };
+
+    let mut parts = press.data.custom_id.splitn(4, ':');
+
+    if parts.next() != Some(RANDOM_CUSTOM_ID_PREFIX) {
+        return Ok(());
+    }
+
+    let (Some(author_id), Some(current), Some(filter)) = (
+        parts
+            .next()
+            .and_then(|id| id.parse().ok())
+            .filter(|id| *id != 0)
+            .map(UserId::new),
+        parts.next().and_then(|id| uuid::Uuid::try_parse(id).ok()),
+        parts.next(),
+    ) else {
+        return Ok(());
+    };
+
+    if press.user.id != author_id {
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("you cannot interact with another user's invoked command!")
+                        .ephemeral(true),
+                ),
+            )
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+
+        return Ok(());
+    }
+
+    let Some(api) = data.mangadex() else {
+        return Ok(());
+    };
+
+    // fetching the manga and its statistics can take longer than discord's response window.
+    press
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when creating response"))?;
+
+    let filter = Some(filter).filter(|f| !f.is_empty());
+
+    let mut edit = EditInteractionResponse::new();
+
+    match pick_random_manga(data, &api, filter, Some(current)).await? {
+        Some((manga_id, manga)) => {
+            let preview = crate::handlers::manga_preview(
+                ctx,
+                data,
+                &api,
+                manga_id,
+                &manga,
+                press.channel_id,
+                press.guild_id,
+            )
+            .await?;
+
+            edit = edit
+                .content("here's your random pick!")
+                .embed(preview.embed)
+                .components(random_components(
+                    author_id,
+                    manga_id,
+                    filter,
+                    preview.buttons,
+                ))
+                .clear_attachments();
+
+            if let Some(cover) = preview.cover {
+                edit = edit.new_attachment(cover);
+            }
+        }
+        None => {
+            edit = edit
+                .content(random_not_found(filter))
+                .embeds(vec![])
+                .components(vec![])
+                .clear_attachments();
+        }
+    }
+
+    press
+        .edit_response(ctx, edit)
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing response"))?;
+
+    Ok(())
+}
+
+/// prefix of the track button's custom id, `mt:<manga>`, shown on anilist and myanimelist link
+/// previews.
+pub const TRACK_CUSTOM_ID_PREFIX: &str = "mt";
+
+/// handles the track button on manga previews by adding the manga to the tracking list.
+///
+/// interactions whose custom id does not belong to a track button are ignored.
+pub async fn handle_track_interaction(
+    ctx: &poise::serenity_prelude::Context,
+    data: &Data,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    let Interaction::Component(press) = interaction else {
+        return Ok(());
+    };
+
+    let Some(uuid) = press
+        .data
+        .custom_id
+        .strip_prefix(TRACK_CUSTOM_ID_PREFIX)
+        .and_then(|id| id.strip_prefix(':'))
+        .and_then(|id| uuid::Uuid::try_parse(id).ok())
+    else {
+        return Ok(());
+    };
+
+    let ephemeral = |content: String| {
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(content)
+                .ephemeral(true),
+        )
+    };
+
+    let (Some(api), Some(_)) = (data.mangadex(), data.mdlist_id) else {
+        press
+            .create_response(
+                ctx,
+                ephemeral("the mangadex client is not set up, so manga can't be tracked.".into()),
+            )
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+
+        return Ok(());
+    };
+
+    let hyphenated = uuid.hyphenated();
+    let existing_title = sqlx::query!(
+        r#"
+            SELECT
+                title
+            FROM manga
+            WHERE manga_dex_id = $1;
+        "#,
+        hyphenated,
+    )
+    .fetch_optional(&data.db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga from database"),
+    )?;
+
+    if let Some(existing) = existing_title {
+        press
+            .create_response(
+                ctx,
+                ephemeral(format!(
+                    "**{}** is already in the tracking list.",
+                    existing.title.as_deref().unwrap_or("this manga")
+                )),
+            )
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+
+        return Ok(());
+    }
+
+    // fetching the manga and updating the mdlist can take longer than discord's response window.
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when creating response"))?;
+
+    // the deferred response stays at "thinking..." until it is edited, so errors are reported
+    // there before being passed on.
+    let result = async {
+        let manga = api.manga(uuid).await.inspect_err(
+            |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga"),
+        )?;
+
+        let latest_chapter_date = latest_chapter_date(&api, uuid).await?;
+        let resp_string = insert_tracked_manga(data, &api, &manga, latest_chapter_date).await?;
+
+        Ok::<_, Error>((manga.title.unwrap_or_else(|| uuid.to_string()), resp_string))
+    }
+    .await;
+
+    let content = match &result {
+        Ok((title, Some(resp_string))) => {
+            resp_string.clone()
+                + &format!(
+                    "<@{}> added [**{}**](https://mangadex.org/title/{}) to the tracking list!",
+                    press.user.id, title, uuid
+                )
+        }
+        Ok((title, None)) => format!("**{}** is already in the tracking list.", title),
+        Err(_) => "couldn't add the manga to the tracking list. try again later!".to_string(),
+    };
+
+    press
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new()
+                .allowed_mentions(CreateAllowedMentions::new())
+                .content(content),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing response"))?;
+
+    result.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::mangadex::fake::{FakeMangaDex, chapter, manga_info};
+
+    async fn test_db() -> sqlx::Pool<sqlx::Sqlite> {
+        // every connection to `sqlite::memory:` gets its own database, so only allow one.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        db
+    }
+
+    async fn track(db: &sqlx::Pool<sqlx::Sqlite>, manga: &MangaInfo) {
+        sqlx::query("INSERT INTO manga (manga_dex_id, last_updated, status) VALUES ($1, $2, $3);")
+            .bind(manga.id.hyphenated().to_string())
+            .bind(time::OffsetDateTime::now_utc())
+            .bind(&manga.status)
+            .execute(db)
+            .await
+            .unwrap();
+
+        cache_manga_tags(db, manga).await.unwrap();
+    }
+
+    const FRIEREN: &str = "Frieren: Beyond Journey's End";
+
+    #[test]
+    fn normalizes_titles() {
+        assert_eq!(normalize_title(FRIEREN), "frieren beyond journey s end");
+        assert_eq!(normalize_title("  Spy×Family!! "), "spy family");
+        assert_eq!(normalize_title("???"), "");
+    }
+
+    #[test]
+    fn scores_closer_matches_higher() {
+        assert_eq!(
+            fuzzy_score("frieren: beyond journey's end", FRIEREN),
+            Some(5)
+        );
+        assert_eq!(fuzzy_score("FRIEREN", FRIEREN), Some(4));
+        assert_eq!(fuzzy_score("beyond journey", FRIEREN), Some(3));
+        assert_eq!(fuzzy_score("end frieren", FRIEREN), Some(2));
+    }
+
+    #[test]
+    fn matches_titles_with_typos() {
+        assert_eq!(fuzzy_score("freiren beyond", FRIEREN), Some(1));
+        assert_eq!(fuzzy_score("frieren bayond", FRIEREN), Some(1));
+
+        // short queries leave no room for typos.
+        assert_eq!(fuzzy_score("frx", FRIEREN), None);
+        assert_eq!(fuzzy_score("naruto", FRIEREN), None);
+        assert_eq!(fuzzy_score("   ", FRIEREN), None);
+    }
+
+    #[test]
+    fn keeps_every_title_tied_for_the_best_match() {
+        let titles = || {
+            [(1, "Blue Box"), (2, "Blue Lock"), (3, "Blue Period")]
+                .map(|(id, title)| (id, title.to_string()))
+        };
+
+        assert_eq!(
+            best_matches("blue", titles()),
+            vec![
+                (1, "Blue Box".to_string()),
+                (2, "Blue Lock".to_string()),
+                (3, "Blue Period".to_string()),
+            ]
+        );
+        assert_eq!(
+            best_matches("blue box", titles()),
+            vec![(1, "Blue Box".to_string())]
+        );
+        assert!(best_matches("frieren", titles()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn finds_manga_by_nickname() {
+        let db = test_db().await;
+        let manga_id = Uuid::from_u128(1).hyphenated();
+
+        sqlx::query("INSERT INTO manga (manga_dex_id, last_updated, title) VALUES ($1, $2, $3);")
+            .bind(manga_id.to_string())
+            .bind(time::OffsetDateTime::now_utc())
+            .bind(FRIEREN)
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO manga_aliases (manga_dex_id, alias) VALUES ($1, 'sousou');")
+            .bind(manga_id.to_string())
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let found = find_alias(&db, "SOUSOU").await.unwrap().unwrap();
+        assert_eq!(found.id, manga_id);
+        assert_eq!(found.title.as_deref(), Some(FRIEREN));
+        assert!(found.tracked);
+
+        assert!(find_alias(&db, "frieren").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn random_candidates_match_cached_details() {
+        let db = test_db().await;
+
+        let mut romance = manga_info(Uuid::from_u128(1), "romance manga");
+        romance.tags = vec!["Romance".to_string(), "Comedy".to_string()];
+        romance.demographic = Some("shoujo".to_string());
+        let mut finished = manga_info(Uuid::from_u128(2), "finished manga");
+        finished.status = "completed".to_string();
+        finished.demographic = Some("seinen".to_string());
+
+        track(&db, &romance).await;
+        track(&db, &finished).await;
+
+        let ids = |candidates: Vec<uuid::fmt::Hyphenated>| {
+            let mut ids = candidates
+                .into_iter()
+                .map(|id| id.into_uuid())
+                .collect::<Vec<Uuid>>();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(
+            ids(random_candidates(&db, None).await.unwrap()),
+            vec![romance.id, finished.id]
+        );
+        assert_eq!(
+            ids(random_candidates(&db, Some("romance")).await.unwrap()),
+            vec![romance.id]
+        );
+        assert_eq!(
+            ids(random_candidates(&db, Some("SHOUJO")).await.unwrap()),
+            vec![romance.id]
+        );
+        assert_eq!(
+            ids(random_candidates(&db, Some("Completed")).await.unwrap()),
+            vec![finished.id]
+        );
+        assert!(
+            random_candidates(&db, Some("horror"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_tags_are_replaced() {
+        let db = test_db().await;
+
+        let mut manga = manga_info(Uuid::from_u128(1), "some manga");
+        manga.tags = vec!["Romance".to_string()];
+        track(&db, &manga).await;
+
+        manga.tags = vec!["Drama".to_string()];
+        cache_manga_tags(&db, &manga).await.unwrap();
+
+        assert!(
+            random_candidates(&db, Some("romance"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            random_candidates(&db, Some("drama")).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn latest_chapter_date_uses_newest_chapter() {
+        let manga_id = Uuid::from_u128(1);
+        let mut newest = chapter(manga_id, "12");
+        newest.publish_at = Some(time::macros::datetime!(2026-10-01 12:00 UTC));
+
+        let mut api = FakeMangaDex::default();
+        api.latest_chapters.insert(manga_id, newest);
+
+        assert_eq!(
+            latest_chapter_date(&api, manga_id).await.unwrap(),
+            Some(time::macros::datetime!(2026-10-01 12:00 UTC))
+        );
+        assert_eq!(
+            latest_chapter_date(&api, Uuid::from_u128(2)).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn set_followed_passes_errors_through() {
+        let manga_id = Uuid::from_u128(1);
+        let mut api = FakeMangaDex::default();
+
+        set_followed(&api, manga_id, true).await.unwrap();
+        assert!(api.followed.lock().unwrap().contains(&manga_id));
+
+        set_followed(&api, manga_id, false).await.unwrap();
+        assert!(api.followed.lock().unwrap().is_empty());
+
+        api.failing.insert(manga_id);
+        assert!(set_followed(&api, manga_id, true).await.is_err());
+    }
+}
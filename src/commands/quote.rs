@@ -1,7 +1,8 @@
 use poise::serenity_prelude::*;
 
 use crate::models::quotes::Quote;
-use crate::{Context, Error};
+use crate::paginator::{self, Page, PageSource};
+use crate::{Context, Data, Error};
 
 #[tracing::instrument(skip(ctx))]
 #[poise::command(
@@ -111,6 +112,43 @@ pub async fn list_quotes(ctx: Context<'_>) -> Result<(), Error> {
         .await
         .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
 
+    let pages = quote_list_pages(ctx.data()).await?;
+
+    if pages.is_empty() {
+        msg.edit(
+            ctx,
+            poise::CreateReply::default()
+                .reply(true)
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content("no quotes found in database!"),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+        return Ok(());
+    }
+
+    msg.edit(
+        ctx,
+        paginator::reply(&QUOTE_LIST, ctx.author().id, &pages)
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content("here's your quotes list!"),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+    Ok(())
+}
+
+/// the quote list, paginated from the database.
+pub const QUOTE_LIST: PageSource = PageSource {
+    kind: "quote",
+    pages: |data| Box::pin(quote_list_pages(data)),
+};
+
+/// builds the pages of the quote list.
+async fn quote_list_pages(data: &Data) -> Result<Vec<Page>, Error> {
     let rows = sqlx::query!(
         r#"
             SELECT
@@ -124,7 +162,7 @@ pub async fn list_quotes(ctx: Context<'_>) -> Result<(), Error> {
             ORDER BY q.id;
         "#,
     )
-    .fetch_all(&ctx.data().db)
+    .fetch_all(&data.db)
     .await
     .inspect_err(
         |e| tracing::error!(err = ?e, "an error occurred when fetching quotes from database"),
@@ -147,8 +185,7 @@ pub async fn list_quotes(ctx: Context<'_>) -> Result<(), Error> {
         })
         .collect();
 
-    let mut pages: Vec<String> = vec![];
-    let mut current_page: usize = 0;
+    let mut pages: Vec<Page> = vec![];
 
     for (page, chunk) in quotes.chunks(10).enumerate() {
         let mut quote_list_str = String::new();
@@ -168,139 +205,15 @@ pub async fn list_quotes(ctx: Context<'_>) -> Result<(), Error> {
             quote_list_str = quote_list_str + &entry_str;
         }
 
-        pages.push(quote_list_str);
+        pages.push(Page {
+            label: chunk[0].title.clone(),
+            embed: CreateEmbed::default()
+                .title("list of quotes")
+                .description(quote_list_str),
+        });
     }
 
-    if pages.is_empty() {
-        msg.edit(
-            ctx,
-            poise::CreateReply::default()
-                .reply(true)
-                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                .content("no quotes found in database!"),
-        )
-        .await
-        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-
-        return Ok(());
-    }
-
-    let ctx_id = ctx.id();
-    let author_id = ctx.author().id;
-    let first_id = format!("{}first", ctx_id);
-    let last_id = format!("{}last", ctx_id);
-    let prev_id = format!("{}prev", ctx_id);
-    let next_id = format!("{}next", ctx_id);
-
-    msg.edit(
-        ctx,
-        poise::CreateReply::default()
-            .reply(true)
-            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-            .content("here's your quotes list!")
-            .embed(
-                CreateEmbed::default()
-                    .title("list of quotes")
-                    .description(pages[0].clone())
-                    .footer(CreateEmbedFooter::new(format!(
-                        "page {}/{}",
-                        current_page + 1,
-                        pages.len(),
-                    ))),
-            )
-            .components(vec![CreateActionRow::Buttons(vec![
-                CreateButton::new(&first_id).emoji('⏮').disabled(true),
-                CreateButton::new(&prev_id).emoji('◀').disabled(true),
-                CreateButton::new(&next_id)
-                    .emoji('▶')
-                    .disabled(current_page == pages.len() - 1),
-                CreateButton::new(&last_id)
-                    .emoji('⏭')
-                    .disabled(current_page == pages.len() - 1),
-            ])]),
-    )
-    .await
-    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-
-    while let Some(press) = collector::ComponentInteractionCollector::new(ctx)
-        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-        .timeout(std::time::Duration::from_secs(60))
-        .await
-    {
-        if press.user.id != author_id {
-            press
-                .create_response(
-                    ctx,
-                    CreateInteractionResponse::Message(
-                        CreateInteractionResponseMessage::new()
-                            .content("you cannot interact with another user's invoked command!")
-                            .ephemeral(true),
-                    ),
-                )
-                .await
-                .inspect_err(
-                    |e| tracing::error!(err = ?e, "an error occurred when creating response"),
-                )?;
-
-            continue;
-        }
-
-        if press.data.custom_id == prev_id {
-            current_page = current_page.saturating_sub(1);
-        } else if press.data.custom_id == next_id {
-            current_page += 1;
-        } else if press.data.custom_id == first_id {
-            current_page = 0;
-        } else if press.data.custom_id == last_id {
-            current_page = pages.len() - 1;
-        } else {
-            continue;
-        }
-
-        press
-            .create_response(
-                ctx,
-                CreateInteractionResponse::UpdateMessage(
-                    CreateInteractionResponseMessage::new()
-                        .embed(
-                            CreateEmbed::default()
-                                .title("list of quotes")
-                                .description(pages[current_page].clone())
-                                .footer(CreateEmbedFooter::new(format!(
-                                    "page {}/{}",
-                                    current_page + 1,
-                                    pages.len(),
-                                ))),
-                        )
-                        .components(vec![CreateActionRow::Buttons(vec![
-                            CreateButton::new(&first_id)
-                                .emoji('⏮')
-                                .disabled(current_page == 0),
-                            CreateButton::new(&prev_id)
-                                .emoji('◀')
-                                .disabled(current_page == 0),
-                            CreateButton::new(&next_id)
-                                .emoji('▶')
-                                .disabled(current_page == pages.len() - 1),
-                            CreateButton::new(&last_id)
-                                .emoji('⏭')
-                                .disabled(current_page == pages.len() - 1),
-                        ])]),
-                ),
-            )
-            .await
-            .inspect_err(
-                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
-            )?;
-    }
-
-    msg.into_message()
-        .await?
-        .edit(ctx, EditMessage::default().components(vec![]))
-        .await
-        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-
-    Ok(())
+    Ok(pages)
 }
 
 #[tracing::instrument(skip(ctx))]
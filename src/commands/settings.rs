@@ -0,0 +1,267 @@
+use poise::ChoiceParameter;
+use poise::serenity_prelude::*;
+
+use crate::embed_controls::forget_embed_controls;
+use crate::models::settings::{AdultPreviewMode, LinkPreviewMode, LinkReplyMode};
+use crate::settings::get_guild_settings;
+use crate::{Context, Error};
+
+/// view or change the bot's settings for this server.
+#[tracing::instrument(skip_all)]
+#[poise::command(
+    prefix_command,
+    subcommand_required,
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "show",
+        "adult_preview",
+        "link_previews",
+        "link_replies",
+        "duplicate_window"
+    )
+)]
+pub async fn settings(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// print the current settings for this server.
+#[tracing::instrument(skip_all)]
+#[poise::command(prefix_command)]
+pub async fn show(ctx: Context<'_>) -> Result<(), Error> {
+    let settings = get_guild_settings(&ctx.data().db, ctx.guild_id().unwrap()).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .embed(
+                CreateEmbed::default()
+                    .title("server settings")
+                    .field(
+                        "adult manga previews",
+                        format!("`{}`", settings.adult_manga_preview.name()),
+                        true,
+                    )
+                    .field(
+                        "link previews",
+                        format!("`{}`", settings.link_previews.name()),
+                        true,
+                    )
+                    .field(
+                        "link replies",
+                        format!("`{}`", settings.link_replies.name()),
+                        true,
+                    )
+                    .field(
+                        "duplicate link window",
+                        match settings.duplicate_window {
+                            0 => "`off`".to_string(),
+                            minutes => format!("`{minutes}` minutes"),
+                        },
+                        true,
+                    ),
+            ),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// change how erotica / pornographic manga previews are shown in non-NSFW channels.
+///
+/// `spoiler` sends the cover as a spoilered attachment, `omit` leaves it out entirely, and
+/// `show` disables the special handling. descriptions are spoilered unless the mode is `show`.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, rename = "adultpreview")]
+pub async fn adult_preview(
+    ctx: Context<'_>,
+    #[description = "one of `spoiler`, `omit` or `show`."] mode: AdultPreviewMode,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO
+                guild_settings (guild_id, adult_manga_preview)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (guild_id)
+            DO UPDATE SET
+                adult_manga_preview = excluded.adult_manga_preview;
+        "#,
+        guild_id,
+        mode,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when updating guild settings"),
+    )?;
+
+    forget_embed_controls(ctx.data(), ctx.guild_id().unwrap());
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(format!(
+                "adult manga previews will now use the `{}` mode.",
+                mode.name()
+            )),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// change how links matched by the embed rules are previewed.
+///
+/// `proxy` replies with the links rewritten to their fixer hosts. `native` fetches the pages and
+/// builds the previews from their metadata, falling back to the fixer hosts when that fails.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, rename = "linkpreviews")]
+pub async fn link_previews(
+    ctx: Context<'_>,
+    #[description = "either `proxy` or `native`."] mode: LinkPreviewMode,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO
+                guild_settings (guild_id, link_previews)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (guild_id)
+            DO UPDATE SET
+                link_previews = excluded.link_previews;
+        "#,
+        guild_id,
+        mode,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when updating guild settings"),
+    )?;
+
+    forget_embed_controls(ctx.data(), ctx.guild_id().unwrap());
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(format!(
+                "links will now be previewed with the `{}` mode.",
+                mode.name()
+            )),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// change how the bot answers messages with links it fixed.
+///
+/// `reply` replies under the original message. `repost` reposts the message with its links fixed
+/// through a webhook, under the author's name and avatar, and deletes the original. the author
+/// can undo a repost with its button. messages with attachments or replies are always replied to.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, rename = "linkreplies")]
+pub async fn link_replies(
+    ctx: Context<'_>,
+    #[description = "either `reply` or `repost`."] mode: LinkReplyMode,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO
+                guild_settings (guild_id, link_replies)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (guild_id)
+            DO UPDATE SET
+                link_replies = excluded.link_replies;
+        "#,
+        guild_id,
+        mode,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when updating guild settings"),
+    )?;
+
+    forget_embed_controls(ctx.data(), ctx.guild_id().unwrap());
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(format!(
+                "fixed links will now use the `{}` mode.",
+                mode.name()
+            )),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
+
+/// change how long a shared link counts as already posted in its channel.
+///
+/// links shared again within this many minutes get a jump link to the first post instead of
+/// another embed. `0` turns this off.
+#[tracing::instrument(skip(ctx))]
+#[poise::command(prefix_command, rename = "duplicatewindow")]
+pub async fn duplicate_window(
+    ctx: Context<'_>,
+    #[description = "the window in minutes, or `0` to turn it off."] minutes: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO
+                guild_settings (guild_id, duplicate_window)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (guild_id)
+            DO UPDATE SET
+                duplicate_window = excluded.duplicate_window;
+        "#,
+        guild_id,
+        minutes,
+    )
+    .execute(&ctx.data().db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when updating guild settings"),
+    )?;
+
+    forget_embed_controls(ctx.data(), ctx.guild_id().unwrap());
+
+    let content = match minutes {
+        0 => "links shared again won't be pointed out anymore.".to_string(),
+        minutes => format!(
+            "links shared again within {minutes} minutes will be pointed to the first post."
+        ),
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .reply(true)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content(content),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    Ok(())
+}
@@ -7,6 +7,9 @@ pub mod music;
 pub mod version;
 pub mod zenless;
 
+#[cfg(test)]
+mod tests;
+
 pub static POISE_VERSION: &str = "0.6.1";
 pub static STARTUP_TIME: LazyLock<std::time::SystemTime> =
     LazyLock::new(std::time::SystemTime::now);
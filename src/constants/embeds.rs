@@ -1,30 +1,59 @@
 use std::sync::LazyLock;
 
-use fancy_regex::Regex;
-
-pub static TWITTER_URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"https?://(?:www\.)?(twitter\.com|x\.com)/([\w.-]+)/status/(\d+)").unwrap()
+/// reddit's `/r/<sub>/s/<code>` share links, which only redirect to the post.
+pub static REDDIT_SHARE_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(r"https?://(?:www\.|old\.)?reddit\.com/r/\w+/s/\w+").unwrap()
 });
 
-pub static TIKTOK_URL_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"https?://vt\.tiktok\.com/(\w+)").unwrap());
-
-pub static PIXIV_ARTWORK_URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"https?://(?:www\.)pixiv\.net/(?:(?P<lang>[a-z]{2})/)?artworks/(?P<id>\d+)(?:/(?P<idx>\d+))?",
+/// pixiv artwork links, with the page of multi-page posts phixiv shows when given `idx`.
+pub static PIXIV_ARTWORK_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"https?://(?:www\.)?pixiv\.net/(?:[a-z]{2}/)?artworks/(?P<id>\d+)(?:/(?P<idx>\d+))?",
     )
     .unwrap()
 });
 
-pub static PIXIV_SHORT_URL_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"https?://(?:www\.)pixiv\.net/i/(\d+)").unwrap());
-
-pub static PIXIV_LEGACY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"https?://(?:www\.)?pixiv\.net/member_illust\.php\?illust_id=(\d+)").unwrap()
-});
-
-pub static FACEBOOK_URL_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"https?://(?:www\.)?facebook\.com/(.*)").unwrap());
-
-pub static REDDIT_URL_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"https?://(?:www\.|old\.)?reddit\.com/(.*)").unwrap());
+/// any link, stopping at the markdown discord puts around them.
+pub static LINK_REGEX: LazyLock<fancy_regex::Regex> =
+    LazyLock::new(|| fancy_regex::Regex::new(r"https?://[^\s<>|`]+").unwrap());
+
+/// query parameters that only track who shared a link and where from, by host. hosts also cover
+/// their subdomains, and `*` covers every host. a trailing `*` matches any parameter starting
+/// with the rest.
+pub static TRACKING_PARAMS: &[(&str, &[&str])] = &[
+    (
+        "*",
+        &[
+            "utm_*", "fbclid", "gclid", "dclid", "msclkid", "igshid", "mc_cid", "mc_eid", "_hsenc",
+            "_hsmi", "ref_src", "ref_url",
+        ],
+    ),
+    ("youtube.com", &["si", "feature", "pp"]),
+    ("youtu.be", &["si", "feature"]),
+    ("spotify.com", &["si", "context", "nd", "dl_branch"]),
+    ("x.com", &["s", "t"]),
+    ("twitter.com", &["s", "t"]),
+    ("instagram.com", &["igsh", "img_index"]),
+    ("threads.net", &["xmt", "slof"]),
+    ("threads.com", &["xmt", "slof"]),
+    (
+        "tiktok.com",
+        &[
+            "_r",
+            "_t",
+            "is_from_webapp",
+            "sender_device",
+            "web_id",
+            "share_app_id",
+        ],
+    ),
+    ("reddit.com", &["share_id", "rdt"]),
+    ("facebook.com", &["mibextid", "rdid", "sfnsn"]),
+];
+
+/// subdomains that serve the same pages as the bare host, stripped when comparing links.
+pub static MIRROR_SUBDOMAINS: &[&str] = &["www.", "m.", "mobile.", "old."];
+
+/// hosts that serve the same pages under another name, and the name links are compared by.
+pub static HOST_ALIASES: &[(&str, &str)] =
+    &[("twitter.com", "x.com"), ("threads.com", "threads.net")];
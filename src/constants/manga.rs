@@ -3,6 +3,20 @@ use std::sync::LazyLock;
 pub static MD_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
     fancy_regex::Regex::new(r"(?<!<)https://mangadex\.org/title/([a-f0-9]{8}-[a-f0-9]{4}-[a-f0-9]{4}-[a-f0-9]{4}-[a-f0-9]{12})(?!>)").unwrap()
 });
+pub static ANILIST_MANGA_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(r"(?<!<)https://anilist\.co/manga/(\d+)(?:/[^\s>]*)?(?!>)").unwrap()
+});
+pub static MAL_MANGA_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"(?<!<)https://(?:www\.)?myanimelist\.net/manga/(\d+)(?:/[^\s>]*)?(?!>)",
+    )
+    .unwrap()
+});
+
+pub static MD_API_URL: &str = "https://api.mangadex.org";
+
+/// the chapter language the tracker announces. there are no per-title language settings yet.
+pub static MD_TRACKED_LANGUAGE: &str = "en";
 
 pub static AZUKI_MANGA: LazyLock<uuid::Uuid> =
     LazyLock::new(|| uuid::Uuid::try_parse("5fed0576-8b94-4f9a-b6a7-08eecd69800d").unwrap());
@@ -5,7 +5,67 @@ pub static YOUTUBE_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
 });
 pub static SPOTIFY_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
     fancy_regex::Regex::new(
-        r"(?:https?://)?(?:open\.)?spotify\.com/(?:track|album)/([a-zA-Z0-9]{22})(?:\?.*)?",
+        r"(?:https?://)?(?:open\.)?spotify\.com/(?:track|album)/([a-zA-Z0-9]{22})(?:\?[^\s<>|`]*)?",
     )
     .unwrap()
 });
+pub static APPLE_MUSIC_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"https?://(?:geo\.)?music\.apple\.com/[a-z]{2}/(?:album|song)/(?:[^\s/<>|`]+/)?(\d+)(?:\?i=\d+)?",
+    )
+    .unwrap()
+});
+pub static TIDAL_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(r"https?://(?:listen\.)?tidal\.com/(?:browse/)?(?:track|album)/(\d+)")
+        .unwrap()
+});
+pub static DEEZER_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"https?://(?:(?:www\.)?deezer\.com/(?:[a-z]{2}/)?(?:track|album)/(\d+)|deezer\.page\.link/(\w+))",
+    )
+    .unwrap()
+});
+pub static SOUNDCLOUD_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"https?://(?:(?:www\.|m\.)?soundcloud\.com/([\w-]+/(?!(?:sets|likes|tracks|albums|reposts)\b)[\w-]+)|on\.soundcloud\.com/(\w+))",
+    )
+    .unwrap()
+});
+pub static BANDCAMP_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(r"https?://([\w-]+)\.bandcamp\.com/(?:track|album)/[\w-]+").unwrap()
+});
+pub static AMAZON_MUSIC_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"https?://music\.amazon\.[a-z.]+/(?:albums|tracks)/(\w+)(?:\?[^\s<>|`]*)?",
+    )
+    .unwrap()
+});
+
+/// the streaming platforms whose links are looked up on songlink, by name.
+pub static MUSIC_PLATFORMS: &[(&str, &LazyLock<fancy_regex::Regex>)] = &[
+    ("youtube", &YOUTUBE_URL_REGEX),
+    ("spotify", &SPOTIFY_URL_REGEX),
+    ("apple music", &APPLE_MUSIC_URL_REGEX),
+    ("tidal", &TIDAL_URL_REGEX),
+    ("deezer", &DEEZER_URL_REGEX),
+    ("soundcloud", &SOUNDCLOUD_URL_REGEX),
+    ("bandcamp", &BANDCAMP_URL_REGEX),
+    ("amazon music", &AMAZON_MUSIC_URL_REGEX),
+];
+
+/// the labels of the platforms songlink returns in `linksByPlatform`, in the order their buttons
+/// are shown. platforms missing here come after these, labelled by their key.
+pub static SONGLINK_PLATFORMS: &[(&str, &str)] = &[
+    ("spotify", "Spotify"),
+    ("appleMusic", "Apple Music"),
+    ("youtubeMusic", "YouTube Music"),
+    ("youtube", "YouTube"),
+    ("tidal", "TIDAL"),
+    ("deezer", "Deezer"),
+    ("soundcloud", "SoundCloud"),
+    ("amazonMusic", "Amazon Music"),
+    ("bandcamp", "Bandcamp"),
+    ("pandora", "Pandora"),
+    ("itunes", "iTunes"),
+    ("amazonStore", "Amazon"),
+];
@@ -0,0 +1,434 @@
+//! offline regression tests for the link regexes, with a corpus of real-world links per site.
+//! each case is a link and what the regex should capture from it, or `None` if it shouldn't
+//! match at all.
+
+use fancy_regex::{Captures, Regex};
+
+use super::embeds::{LINK_REGEX, PIXIV_ARTWORK_URL_REGEX, REDDIT_SHARE_URL_REGEX};
+use super::manga::{ANILIST_MANGA_URL_REGEX, MAL_MANGA_URL_REGEX, MD_URL_REGEX};
+use super::music::{
+    AMAZON_MUSIC_URL_REGEX, APPLE_MUSIC_URL_REGEX, BANDCAMP_URL_REGEX, DEEZER_URL_REGEX,
+    MUSIC_PLATFORMS, SOUNDCLOUD_URL_REGEX, SPOTIFY_URL_REGEX, TIDAL_URL_REGEX, YOUTUBE_URL_REGEX,
+};
+use crate::handlers::songlink_url;
+
+fn check_cases(
+    regex: &Regex,
+    cases: &[(&str, Option<&str>)],
+    group: impl Fn(&Captures) -> Option<String>,
+) {
+    for (text, expected) in cases {
+        let captured = regex
+            .captures(text)
+            .unwrap()
+            .and_then(|captures| group(&captures));
+
+        assert_eq!(captured.as_deref(), *expected, "{}", text);
+    }
+}
+
+fn whole_match(captures: &Captures) -> Option<String> {
+    captures.get(0).map(|m| m.as_str().to_string())
+}
+
+fn first_group(captures: &Captures) -> Option<String> {
+    captures.get(1).map(|m| m.as_str().to_string())
+}
+
+#[test]
+fn youtube_links() {
+    check_cases(
+        &YOUTUBE_URL_REGEX,
+        &[
+            (
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+                Some("dQw4w9WgXcQ"),
+            ),
+            (
+                "https://youtube.com/watch?v=dQw4w9WgXcQ&list=RDdQw4w9WgXcQ",
+                Some("dQw4w9WgXcQ"),
+            ),
+            (
+                "https://youtu.be/dQw4w9WgXcQ?si=AbCdEfGhIjKlMnOp",
+                Some("dQw4w9WgXcQ"),
+            ),
+            (
+                "https://music.youtube.com/watch?v=dQw4w9WgXcQ&feature=share",
+                Some("dQw4w9WgXcQ"),
+            ),
+            ("youtu.be/dQw4w9WgXcQ", Some("dQw4w9WgXcQ")),
+            (
+                "https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+                None,
+            ),
+            ("https://www.youtube.com/@someone", None),
+            ("https://www.youtube.com/watch?v=short", None),
+        ],
+        first_group,
+    );
+}
+
+#[test]
+fn spotify_links() {
+    check_cases(
+        &SPOTIFY_URL_REGEX,
+        &[
+            (
+                "https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT",
+                Some("4cOdK2wGLETKBW3PvgPWqT"),
+            ),
+            (
+                "https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT?si=1a2b3c4d5e6f7a8b",
+                Some("4cOdK2wGLETKBW3PvgPWqT"),
+            ),
+            (
+                "https://open.spotify.com/album/1DFixLWuPkv3KT3TnV35m3",
+                Some("1DFixLWuPkv3KT3TnV35m3"),
+            ),
+            (
+                "spotify.com/track/4cOdK2wGLETKBW3PvgPWqT",
+                Some("4cOdK2wGLETKBW3PvgPWqT"),
+            ),
+            (
+                "https://open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M",
+                None,
+            ),
+            (
+                "https://open.spotify.com/artist/0OdUWJ0sBjDrqHygGUXeCF",
+                None,
+            ),
+        ],
+        first_group,
+    );
+}
+
+#[test]
+fn apple_music_links() {
+    check_cases(
+        &APPLE_MUSIC_URL_REGEX,
+        &[
+            (
+                "https://music.apple.com/us/album/never-gonna-give-you-up/1559523357?i=1559523359",
+                Some(
+                    "https://music.apple.com/us/album/never-gonna-give-you-up/1559523357?i=1559523359",
+                ),
+            ),
+            (
+                "https://music.apple.com/jp/song/never-gonna-give-you-up/1559523359",
+                Some("https://music.apple.com/jp/song/never-gonna-give-you-up/1559523359"),
+            ),
+            (
+                "https://music.apple.com/us/album/1559523357",
+                Some("https://music.apple.com/us/album/1559523357"),
+            ),
+            ("https://music.apple.com/us/artist/rick-astley/669771", None),
+            (
+                "https://music.apple.com/us/playlist/todays-hits/pl.f4d106fed2bd41149aaacabb233eb5eb",
+                None,
+            ),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn tidal_links() {
+    check_cases(
+        &TIDAL_URL_REGEX,
+        &[
+            ("https://tidal.com/browse/track/77640617", Some("77640617")),
+            ("https://listen.tidal.com/album/77640616", Some("77640616")),
+            ("https://tidal.com/track/77640617/u", Some("77640617")),
+            ("https://tidal.com/browse/artist/3528", None),
+        ],
+        first_group,
+    );
+}
+
+#[test]
+fn deezer_links() {
+    check_cases(
+        &DEEZER_URL_REGEX,
+        &[
+            (
+                "https://www.deezer.com/en/track/781592622",
+                Some("https://www.deezer.com/en/track/781592622"),
+            ),
+            (
+                "https://deezer.com/album/119606",
+                Some("https://deezer.com/album/119606"),
+            ),
+            (
+                "https://deezer.page.link/AbCdEf123",
+                Some("https://deezer.page.link/AbCdEf123"),
+            ),
+            ("https://www.deezer.com/en/artist/1109", None),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn soundcloud_links() {
+    check_cases(
+        &SOUNDCLOUD_URL_REGEX,
+        &[
+            (
+                "https://soundcloud.com/rick-astley-official/never-gonna-give-you-up-4",
+                Some("https://soundcloud.com/rick-astley-official/never-gonna-give-you-up-4"),
+            ),
+            (
+                "https://m.soundcloud.com/someone/setsuna",
+                Some("https://m.soundcloud.com/someone/setsuna"),
+            ),
+            (
+                "https://on.soundcloud.com/AbCdEf123",
+                Some("https://on.soundcloud.com/AbCdEf123"),
+            ),
+            (
+                "https://soundcloud.com/rick-astley-official/sets/whenever-you-need-somebody",
+                None,
+            ),
+            ("https://soundcloud.com/rick-astley-official/tracks", None),
+            ("https://soundcloud.com/rick-astley-official", None),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn bandcamp_links() {
+    check_cases(
+        &BANDCAMP_URL_REGEX,
+        &[
+            (
+                "https://someartist.bandcamp.com/track/some-song",
+                Some("https://someartist.bandcamp.com/track/some-song"),
+            ),
+            (
+                "https://some-artist.bandcamp.com/album/some-album",
+                Some("https://some-artist.bandcamp.com/album/some-album"),
+            ),
+            ("https://someartist.bandcamp.com/music", None),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn amazon_music_links() {
+    check_cases(
+        &AMAZON_MUSIC_URL_REGEX,
+        &[
+            (
+                "https://music.amazon.com/albums/B08YS8NS6Y?trackAsin=B08YS6ZYNP",
+                Some("https://music.amazon.com/albums/B08YS8NS6Y?trackAsin=B08YS6ZYNP"),
+            ),
+            (
+                "https://music.amazon.co.jp/tracks/B08YS6ZYNP",
+                Some("https://music.amazon.co.jp/tracks/B08YS6ZYNP"),
+            ),
+            ("https://music.amazon.com/artists/B000QJQ8P6", None),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn music_links_match_one_platform_each() {
+    let links = [
+        "https://youtu.be/dQw4w9WgXcQ",
+        "https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT",
+        "https://music.apple.com/us/album/never-gonna-give-you-up/1559523357?i=1559523359",
+        "https://tidal.com/browse/track/77640617",
+        "https://www.deezer.com/en/track/781592622",
+        "https://soundcloud.com/rick-astley-official/never-gonna-give-you-up-4",
+        "https://someartist.bandcamp.com/track/some-song",
+        "https://music.amazon.com/albums/B08YS8NS6Y?trackAsin=B08YS6ZYNP",
+    ];
+
+    for link in links {
+        let platforms = MUSIC_PLATFORMS
+            .iter()
+            .filter(|(_, regex)| regex.is_match(link).unwrap())
+            .map(|(platform, _)| *platform)
+            .collect::<Vec<&str>>();
+
+        assert_eq!(platforms.len(), 1, "{} matched {:?}", link, platforms);
+    }
+}
+
+#[test]
+fn songlink_urls() {
+    let cases = [
+        (
+            &YOUTUBE_URL_REGEX,
+            "https://youtu.be/dQw4w9WgXcQ?si=AbCdEfGhIjKlMnOp",
+            "https://api.song.link/v1-alpha.1/links?url=https%3A%2F%2Fyoutu.be%2FdQw4w9WgXcQ&userCountry=JP",
+        ),
+        (
+            &YOUTUBE_URL_REGEX,
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://api.song.link/v1-alpha.1/links?url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3DdQw4w9WgXcQ&userCountry=JP",
+        ),
+        (
+            &SPOTIFY_URL_REGEX,
+            "https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT?si=1a2b3c4d5e6f7a8b listen to this",
+            "https://api.song.link/v1-alpha.1/links?url=https%3A%2F%2Fopen.spotify.com%2Ftrack%2F4cOdK2wGLETKBW3PvgPWqT&userCountry=JP",
+        ),
+    ];
+
+    for (regex, text, expected) in cases {
+        let captures = regex.captures(text).unwrap().unwrap();
+
+        assert_eq!(songlink_url(&captures[0]), expected, "{}", text);
+    }
+}
+
+#[test]
+fn mangadex_links() {
+    check_cases(
+        &MD_URL_REGEX,
+        &[
+            (
+                "https://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f",
+                Some("a1c7c817-4e59-43b7-9365-09675a149a6f"),
+            ),
+            (
+                "https://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f/one-piece",
+                Some("a1c7c817-4e59-43b7-9365-09675a149a6f"),
+            ),
+            (
+                "<https://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f>",
+                None,
+            ),
+            (
+                "https://mangadex.org/chapter/a1c7c817-4e59-43b7-9365-09675a149a6f",
+                None,
+            ),
+            (
+                "http://mangadex.org/title/a1c7c817-4e59-43b7-9365-09675a149a6f",
+                None,
+            ),
+        ],
+        first_group,
+    );
+}
+
+#[test]
+fn anilist_links() {
+    check_cases(
+        &ANILIST_MANGA_URL_REGEX,
+        &[
+            ("https://anilist.co/manga/30013", Some("30013")),
+            ("https://anilist.co/manga/30013/One-Piece/", Some("30013")),
+            ("<https://anilist.co/manga/30013/One-Piece/>", None),
+            ("https://anilist.co/anime/21/ONE-PIECE/", None),
+        ],
+        first_group,
+    );
+}
+
+#[test]
+fn myanimelist_links() {
+    check_cases(
+        &MAL_MANGA_URL_REGEX,
+        &[
+            ("https://myanimelist.net/manga/13/One_Piece", Some("13")),
+            ("https://www.myanimelist.net/manga/13", Some("13")),
+            ("<https://myanimelist.net/manga/13/One_Piece>", None),
+            ("https://myanimelist.net/anime/21/One_Piece", None),
+        ],
+        first_group,
+    );
+}
+
+#[test]
+fn reddit_share_links() {
+    check_cases(
+        &REDDIT_SHARE_URL_REGEX,
+        &[
+            (
+                "https://www.reddit.com/r/anime/s/AbC123xyZ",
+                Some("https://www.reddit.com/r/anime/s/AbC123xyZ"),
+            ),
+            (
+                "https://old.reddit.com/r/anime/s/AbC123xyZ",
+                Some("https://old.reddit.com/r/anime/s/AbC123xyZ"),
+            ),
+            (
+                "https://reddit.com/r/anime/s/AbC123xyZ?utm_source=share",
+                Some("https://reddit.com/r/anime/s/AbC123xyZ"),
+            ),
+            (
+                "https://www.reddit.com/r/anime/comments/1abc2de/some_title/",
+                None,
+            ),
+            ("https://www.reddit.com/s/AbC123xyZ", None),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn links_in_markdown() {
+    check_cases(
+        &LINK_REGEX,
+        &[
+            (
+                "look at <https://example.com/a?b=c>",
+                Some("https://example.com/a?b=c"),
+            ),
+            (
+                "`https://example.com/code`",
+                Some("https://example.com/code"),
+            ),
+            (
+                "||https://example.com/spoiler||",
+                Some("https://example.com/spoiler"),
+            ),
+            (
+                "https://example.com/a https://example.com/b",
+                Some("https://example.com/a"),
+            ),
+            ("no links here", None),
+            ("ftp://example.com", None),
+        ],
+        whole_match,
+    );
+}
+
+#[test]
+fn pixiv_artwork_links() {
+    let cases = [
+        (
+            "https://www.pixiv.net/en/artworks/12345678",
+            Some(("12345678", None)),
+        ),
+        (
+            "https://www.pixiv.net/artworks/12345678",
+            Some(("12345678", None)),
+        ),
+        (
+            "https://pixiv.net/artworks/12345678/3",
+            Some(("12345678", Some("3"))),
+        ),
+        ("https://www.pixiv.net/users/123456", None),
+        (
+            "https://www.pixiv.net/member_illust.php?illust_id=12345678",
+            None,
+        ),
+    ];
+
+    for (text, expected) in cases {
+        let captures = PIXIV_ARTWORK_URL_REGEX.captures(text).unwrap();
+        let captured = captures.as_ref().map(|captures| {
+            (
+                &captures["id"],
+                captures.name("idx").map(|idx| idx.as_str()),
+            )
+        });
+
+        assert_eq!(captured, expected, "{}", text);
+    }
+}
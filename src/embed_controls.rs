@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use poise::serenity_prelude::{ChannelId, GuildId, UserId};
+use sqlx::{Pool, Sqlite};
+
+use crate::Data;
+use crate::models::settings::GuildSettings;
+use crate::settings::get_guild_settings;
+
+/// what the embed handler looks up for every message in a guild.
+#[derive(Clone, Debug)]
+pub struct GuildEmbedControls {
+    pub allowed: Vec<ChannelId>,
+    pub denied: Vec<ChannelId>,
+    /// the names of the embed rules the guild turned off.
+    pub disabled_rules: Vec<String>,
+    pub settings: GuildSettings,
+}
+
+impl GuildEmbedControls {
+    /// whether links are rewritten in a channel. denied channels never are, and once a guild
+    /// allows any channel, only those are.
+    pub fn is_channel_enabled(&self, channel_id: ChannelId) -> bool {
+        !self.denied.contains(&channel_id)
+            && (self.allowed.is_empty() || self.allowed.contains(&channel_id))
+    }
+}
+
+/// the embed controls of the guilds messages were seen in, so they aren't fetched for every
+/// message. a guild's entry is dropped whenever a command changes its controls.
+pub type EmbedControls = Arc<RwLock<HashMap<GuildId, GuildEmbedControls>>>;
+
+/// the users who asked the bot not to rewrite their links.
+pub type EmbedOptouts = Arc<RwLock<HashSet<UserId>>>;
+
+/// whether a user asked the bot not to rewrite their links.
+pub fn is_opted_out(data: &Data, user_id: UserId) -> bool {
+    data.embed_optouts.read().unwrap().contains(&user_id)
+}
+
+/// every user who opted out of having their links rewritten.
+pub async fn load_embed_optouts(db: &Pool<Sqlite>) -> Result<HashSet<UserId>, sqlx::Error> {
+    let optouts = sqlx::query!(
+        r#"
+            SELECT
+                user_id
+            FROM embed_optouts;
+        "#
+    )
+    .fetch_all(db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching embed opt-outs"))?;
+
+    Ok(optouts
+        .into_iter()
+        .filter_map(|optout| optout.user_id.parse::<u64>().ok())
+        .map(UserId::new)
+        .collect())
+}
+
+/// the embed controls of a guild, fetching them if they aren't cached.
+pub async fn get_embed_controls(
+    data: &Data,
+    guild_id: GuildId,
+) -> Result<GuildEmbedControls, sqlx::Error> {
+    if let Some(controls) = data.embed_controls.read().unwrap().get(&guild_id) {
+        return Ok(controls.clone());
+    }
+
+    let (allowed, denied) = get_embed_channels(&data.db, guild_id).await?;
+    let controls = GuildEmbedControls {
+        allowed,
+        denied,
+        disabled_rules: get_disabled_rules(&data.db, guild_id).await?,
+        settings: get_guild_settings(&data.db, guild_id).await?,
+    };
+
+    data.embed_controls
+        .write()
+        .unwrap()
+        .insert(guild_id, controls.clone());
+
+    Ok(controls)
+}
+
+/// drop a guild's cached embed controls, so they are fetched again after a change.
+pub fn forget_embed_controls(data: &Data, guild_id: GuildId) {
+    data.embed_controls.write().unwrap().remove(&guild_id);
+}
+
+/// the channels of a guild on its embed allow list and deny list.
+async fn get_embed_channels(
+    db: &Pool<Sqlite>,
+    guild_id: GuildId,
+) -> Result<(Vec<ChannelId>, Vec<ChannelId>), sqlx::Error> {
+    let guild_id = guild_id.to_string();
+
+    let channels = sqlx::query!(
+        r#"
+            SELECT
+                channel_id,
+                allowed AS "allowed: bool"
+            FROM embed_channels
+            WHERE guild_id = $1
+            ORDER BY id;
+        "#,
+        guild_id,
+    )
+    .fetch_all(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when fetching embed channels"),
+    )?;
+
+    let mut allowed = vec![];
+    let mut denied = vec![];
+
+    for channel in channels {
+        let Ok(channel_id) = channel.channel_id.parse::<u64>() else {
+            continue;
+        };
+
+        if channel.allowed {
+            allowed.push(ChannelId::new(channel_id));
+        } else {
+            denied.push(ChannelId::new(channel_id));
+        }
+    }
+
+    Ok((allowed, denied))
+}
+
+/// the names of the embed rules a guild turned off.
+async fn get_disabled_rules(
+    db: &Pool<Sqlite>,
+    guild_id: GuildId,
+) -> Result<Vec<String>, sqlx::Error> {
+    let guild_id = guild_id.to_string();
+
+    let rules = sqlx::query!(
+        r#"
+            SELECT
+                r.name
+            FROM disabled_embed_rules d
+            INNER JOIN embed_rules r ON r.id = d.rule_id
+            WHERE d.guild_id = $1;
+        "#,
+        guild_id,
+    )
+    .fetch_all(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when fetching disabled embed rules"),
+    )?;
+
+    Ok(rules.into_iter().map(|rule| rule.name).collect())
+}
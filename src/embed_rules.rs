@@ -0,0 +1,819 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use sqlx::{Pool, Sqlite};
+
+use crate::Data;
+use crate::constants::embeds::{LINK_REGEX, REDDIT_SHARE_URL_REGEX};
+use crate::models::embeds::{CompiledEmbedRule, EmbedRule};
+use crate::tracking_params::clean_url;
+
+/// the compiled embed rules shared by the message handler and the `embed` commands.
+pub type EmbedRules = Arc<RwLock<Vec<CompiledEmbedRule>>>;
+
+/// whether each fixer host answered its last health check.
+pub type FixerHealth = Arc<RwLock<HashMap<String, bool>>>;
+
+/// how often fixer hosts are probed, in seconds.
+pub const FIXER_HEALTH_CHECK_INTERVAL: u64 = 300;
+
+const FIXER_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn get_embed_rules(db: &Pool<Sqlite>) -> Result<Vec<EmbedRule>, sqlx::Error> {
+    let rules = sqlx::query!(
+        r#"
+            SELECT
+                id AS "id!",
+                name,
+                pattern,
+                template
+            FROM embed_rules
+            ORDER BY id;
+        "#
+    )
+    .fetch_all(db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when fetching embed rules"))?;
+
+    let hosts = sqlx::query!(
+        r#"
+            SELECT
+                rule_id,
+                host
+            FROM embed_rule_hosts
+            ORDER BY rule_id, position;
+        "#
+    )
+    .fetch_all(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when fetching embed rule hosts"),
+    )?;
+
+    Ok(rules
+        .into_iter()
+        .map(|rule| EmbedRule {
+            hosts: hosts
+                .iter()
+                .filter(|host| host.rule_id == rule.id)
+                .map(|host| host.host.clone())
+                .collect(),
+            id: rule.id,
+            name: rule.name,
+            pattern: rule.pattern,
+            template: rule.template,
+        })
+        .collect())
+}
+
+/// compile the embed rules in the database. rules that no longer compile are skipped.
+pub async fn load_embed_rules(db: &Pool<Sqlite>) -> Result<Vec<CompiledEmbedRule>, sqlx::Error> {
+    let rules = get_embed_rules(db).await?;
+
+    Ok(rules
+        .iter()
+        .filter_map(|rule| {
+            CompiledEmbedRule::try_from(rule)
+                .inspect_err(
+                    |e| tracing::warn!(err = %e, name = %rule.name, "skipping invalid embed rule"),
+                )
+                .ok()
+        })
+        .collect())
+}
+
+/// reload the embed rules from the database, so changes apply without a restart.
+pub async fn reload_embed_rules(data: &Data) -> Result<usize, sqlx::Error> {
+    let rules = load_embed_rules(&data.db).await?;
+    let count = rules.len();
+
+    *data.embed_rules.write().unwrap() = rules;
+
+    tracing::info!(count, "reloaded embed rules");
+
+    Ok(count)
+}
+
+/// where code and spoilers are in a message's markdown.
+#[derive(Debug, Default)]
+struct MarkdownRegions {
+    code: Vec<Range<usize>>,
+    spoilers: Vec<Range<usize>>,
+}
+
+impl MarkdownRegions {
+    fn parse(text: &str) -> Self {
+        let bytes = text.as_bytes();
+        let mut regions = MarkdownRegions::default();
+        let mut i = 0;
+
+        // `i` steps through bytes, so it can land inside a char. only slice `bytes` with it; the
+        // markers are all ascii, so the regions found always start and end on char boundaries.
+        let find = |from: usize, marker: &str| {
+            bytes[from..]
+                .windows(marker.len())
+                .position(|window| window == marker.as_bytes())
+                .map(|idx| from + idx)
+        };
+
+        while i < bytes.len() {
+            let (marker, is_code) = if bytes[i..].starts_with(b"```") {
+                ("```", true)
+            } else if bytes[i] == b'`' {
+                ("`", true)
+            } else if bytes[i..].starts_with(b"||") {
+                ("||", false)
+            } else {
+                i += 1;
+                continue;
+            };
+
+            // unclosed markers are shown as-is by discord.
+            let Some(end) = find(i + marker.len(), marker) else {
+                i += marker.len();
+                continue;
+            };
+
+            let region = i..end + marker.len();
+            i = region.end;
+
+            if is_code {
+                regions.code.push(region);
+            } else {
+                regions.spoilers.push(region);
+            }
+        }
+
+        regions
+    }
+
+    fn in_code(&self, link: &Range<usize>) -> bool {
+        self.code.iter().any(|code| code.contains(&link.start))
+    }
+
+    fn in_spoiler(&self, link: &Range<usize>) -> bool {
+        self.spoilers
+            .iter()
+            .any(|spoiler| spoiler.contains(&link.start))
+    }
+}
+
+/// whether a link is wrapped in `<>`, which discord uses to suppress its embed. only the opening
+/// bracket is checked, since greedy patterns can swallow the closing one.
+fn is_suppressed(text: &str, link: &Range<usize>) -> bool {
+    text[..link.start].ends_with('<')
+}
+
+/// a link in a message matched by one of the embed rules, or carrying tracking parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchedLink {
+    /// the link as it appears in the message.
+    pub link: String,
+    /// what the link is rewritten into, or `None` if all of the rule's fixer hosts are down.
+    pub url: Option<String>,
+    /// whether the link is in a spoiler.
+    pub spoiler: bool,
+}
+
+/// the links in `text` the user didn't suppress with `<>` or put in code, in the order they
+/// appear. links found more than once are only kept the first time.
+fn visible_links(text: &str, mut matches: Vec<(Range<usize>, Option<String>)>) -> Vec<MatchedLink> {
+    let regions = MarkdownRegions::parse(text);
+
+    matches.retain(|(link, _)| !regions.in_code(link) && !is_suppressed(text, link));
+    matches.sort_by_key(|(link, _)| link.start);
+
+    let mut links: Vec<MatchedLink> = vec![];
+
+    for (range, url) in matches {
+        let link = text[range.clone()].to_string();
+
+        if links.iter().any(|matched| matched.link == link) {
+            continue;
+        }
+
+        links.push(MatchedLink {
+            link,
+            url,
+            spoiler: regions.in_spoiler(&range),
+        });
+    }
+
+    links
+}
+
+/// every link in `text` matched by any rule, in the order the links appear. links matched more
+/// than once are only kept the first time.
+///
+/// the share links in `shares` are matched by the posts they were resolved to, but kept as they
+/// were posted, so replies and duplicate checks see what the user sent. links the user suppressed
+/// with `<>` or put in code are left out.
+pub fn match_links(
+    rules: &[&CompiledEmbedRule],
+    text: &str,
+    shares: &[(String, String)],
+    is_healthy: impl Fn(&str) -> bool,
+) -> Vec<MatchedLink> {
+    let mut matches = vec![];
+
+    // share links go first, so they win over rules matching the share link itself.
+    for (link, post) in shares {
+        let Some((_, url)) = rules
+            .iter()
+            .find_map(|rule| rule.rewrite_matches(post, &is_healthy).into_iter().next())
+        else {
+            continue;
+        };
+
+        matches.extend(
+            text.match_indices(link.as_str())
+                .map(|(start, _)| (start..start + link.len(), url.clone())),
+        );
+    }
+
+    matches.extend(
+        rules
+            .iter()
+            .flat_map(|rule| rule.rewrite_matches(text, &is_healthy)),
+    );
+
+    visible_links(text, matches)
+}
+
+/// every link in `text` with tracking parameters, with `url` being the link without them.
+///
+/// links the user suppressed with `<>` or put in code are left out.
+pub fn tracked_links(text: &str) -> Vec<MatchedLink> {
+    let matches = LINK_REGEX
+        .find_iter(text)
+        .filter_map(|link| link.ok())
+        .filter_map(|link| Some((link.start()..link.end(), Some(clean_url(link.as_str())?))))
+        .collect();
+
+    visible_links(text, matches)
+}
+
+/// the url a matched link is rewritten into, wrapped in a spoiler if the link was in one.
+fn rewritten_url(link: &MatchedLink) -> Option<String> {
+    let url = link.url.as_ref()?;
+
+    Some(if link.spoiler {
+        format!("||{}||", url)
+    } else {
+        url.clone()
+    })
+}
+
+/// the urls matched links are rewritten into, in order, with links in spoilers rewritten into
+/// spoilers. links rewritten to the same url more than once are only kept the first time, and
+/// rules that leave a link as it was don't produce a url.
+pub fn rewritten_urls<'a>(links: impl IntoIterator<Item = &'a MatchedLink>) -> Vec<String> {
+    let mut urls: Vec<String> = vec![];
+
+    for link in links {
+        if link.url.as_ref() == Some(&link.link) {
+            continue;
+        }
+
+        match rewritten_url(link) {
+            Some(url) if !urls.contains(&url) => urls.push(url),
+            _ => {}
+        }
+    }
+
+    urls
+}
+
+/// whether a fixer host is up. hosts that haven't been checked yet are assumed to be.
+pub fn is_host_healthy(health: &HashMap<String, bool>, host: &str) -> bool {
+    health.get(host).copied().unwrap_or(true)
+}
+
+async fn probe_host(client: &reqwest::Client, host: &str) -> bool {
+    match client
+        .head(format!("https://{}/", host))
+        .timeout(FIXER_HEALTH_CHECK_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(res) => !res.status().is_server_error(),
+        Err(e) => {
+            tracing::debug!(err = ?e, host = %host, "fixer host health check failed");
+            false
+        }
+    }
+}
+
+/// probe every fixer host used by the embed rules and record which ones are up.
+pub async fn check_fixer_hosts(data: &Data) {
+    let mut hosts = data
+        .embed_rules
+        .read()
+        .unwrap()
+        .iter()
+        .flat_map(|rule| rule.hosts.iter().cloned())
+        .collect::<Vec<String>>();
+
+    hosts.sort();
+    hosts.dedup();
+
+    let results = futures::future::join_all(
+        hosts
+            .iter()
+            .map(|host| probe_host(&data.reqwest_client, host)),
+    )
+    .await;
+
+    let mut health = data.fixer_health.write().unwrap();
+    let previous = std::mem::take(&mut *health);
+
+    for (host, healthy) in hosts.into_iter().zip(results) {
+        match (previous.get(&host).copied().unwrap_or(true), healthy) {
+            (true, false) => tracing::warn!(host = %host, "fixer host is down"),
+            (false, true) => tracing::info!(host = %host, "fixer host is back up"),
+            _ => {}
+        }
+
+        health.insert(host, healthy);
+    }
+}
+
+/// where share links were last resolved to, and when. links that couldn't be resolved are
+/// remembered as `None`.
+pub type ShareLinks = Arc<RwLock<HashMap<String, (Instant, Option<String>)>>>;
+
+/// how long a resolved share link is remembered.
+const SHARE_LINK_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// how long a share link that couldn't be resolved is remembered, so a link posted again and
+/// again doesn't wait on reddit every time.
+const FAILED_SHARE_LINK_CACHE_TTL: Duration = Duration::from_secs(300);
+
+const SHARE_LINK_RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// whether a cached share link resolution is still worth using.
+fn is_fresh((resolved_at, url): &(Instant, Option<String>)) -> bool {
+    let ttl = if url.is_some() {
+        SHARE_LINK_CACHE_TTL
+    } else {
+        FAILED_SHARE_LINK_CACHE_TTL
+    };
+
+    resolved_at.elapsed() < ttl
+}
+
+/// the post a share link redirected to, without the tracking query, or `None` if it didn't end up
+/// at a post.
+fn reddit_post_url(url: &reqwest::Url) -> Option<String> {
+    if !url
+        .host_str()
+        .is_some_and(|host| host == "reddit.com" || host.ends_with(".reddit.com"))
+        || REDDIT_SHARE_URL_REGEX
+            .is_match(url.as_str())
+            .unwrap_or(false)
+    {
+        return None;
+    }
+
+    Some(format!("https://www.reddit.com{}", url.path()))
+}
+
+/// follow a reddit share link's redirect to the post it points at, without the tracking query.
+async fn resolve_share_link(client: &reqwest::Client, link: &str) -> Option<String> {
+    let res = client
+        .get(link)
+        .timeout(SHARE_LINK_RESOLVE_TIMEOUT)
+        .send()
+        .await
+        .inspect_err(|e| tracing::warn!(err = ?e, link = %link, "an error occurred when resolving share link"))
+        .ok()?;
+
+    // reddit may refuse the final page, but the redirect has already been followed by then.
+    let url = reddit_post_url(res.url());
+
+    if url.is_none() {
+        tracing::debug!(link = %link, resolved = %res.url(), "share link didn't resolve to a post");
+    }
+
+    url
+}
+
+/// the share links in `text` and the posts they point at, so the embed rules can match them. links
+/// that can't be resolved are left out.
+pub async fn resolve_share_links(data: &Data, text: &str) -> Vec<(String, String)> {
+    let client = &data.reqwest_client;
+
+    resolve_share_links_with(&data.share_links, text, |link| async move {
+        resolve_share_link(client, &link).await
+    })
+    .await
+}
+
+/// [`resolve_share_links`], with `resolve` following the links missing from `share_links`.
+async fn resolve_share_links_with<F, Fut>(
+    share_links: &ShareLinks,
+    text: &str,
+    resolve: F,
+) -> Vec<(String, String)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<String>>,
+{
+    let links = REDDIT_SHARE_URL_REGEX
+        .find_iter(text)
+        .filter_map(|link| link.ok())
+        .map(|link| link.as_str().to_string())
+        .collect::<Vec<String>>();
+
+    let mut resolved = vec![];
+
+    for link in links {
+        let cached = share_links
+            .read()
+            .unwrap()
+            .get(&link)
+            .filter(|entry| is_fresh(entry))
+            .map(|(_, url)| url.clone());
+
+        let url = match cached {
+            Some(url) => url,
+            None => {
+                let url = resolve(link.clone()).await;
+
+                let mut share_links = share_links.write().unwrap();
+                share_links.retain(|_, entry| is_fresh(entry));
+                share_links.insert(link.clone(), (Instant::now(), url.clone()));
+
+                url
+            }
+        };
+
+        if let Some(url) = url
+            && !resolved.iter().any(|(shared, _)| *shared == link)
+        {
+            resolved.push((link, url));
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    fn region_text<'a>(text: &'a str, regions: &[Range<usize>]) -> Vec<&'a str> {
+        regions.iter().map(|region| &text[region.clone()]).collect()
+    }
+
+    #[test]
+    fn parses_regions_around_non_ascii_text() {
+        let text = "héllo 🎉 `código 🚀` ünï ||spöiler 🌸|| ✨```\nブロック 🎌\n``` 終わり 🎉";
+        let regions = MarkdownRegions::parse(text);
+
+        assert_eq!(
+            region_text(text, &regions.code),
+            vec!["`código 🚀`", "```\nブロック 🎌\n```"]
+        );
+        assert_eq!(region_text(text, &regions.spoilers), vec!["||spöiler 🌸||"]);
+    }
+
+    #[test]
+    fn parses_unclosed_markers_after_non_ascii_text() {
+        let text = "🎉 ||ünclosed 🌸 ```é";
+        let regions = MarkdownRegions::parse(text);
+
+        assert!(regions.code.is_empty());
+        assert!(regions.spoilers.is_empty());
+    }
+
+    async fn seeded_rules() -> Vec<CompiledEmbedRule> {
+        // every connection to `sqlite::memory:` gets its own database, so only allow one.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        load_embed_rules(&db).await.unwrap()
+    }
+
+    fn rewrite_links(
+        rules: &[CompiledEmbedRule],
+        text: &str,
+        is_healthy: impl Fn(&str) -> bool,
+    ) -> Vec<String> {
+        let rules = rules.iter().collect::<Vec<&CompiledEmbedRule>>();
+
+        rewritten_urls(&match_links(&rules, text, &[], is_healthy))
+    }
+
+    async fn rewrite(text: &str) -> Vec<String> {
+        rewrite_links(&seeded_rules().await, text, |_| true)
+    }
+
+    /// links for every seeded rule and what they're rewritten into with every fixer host up, or
+    /// `None` for links that should be left alone.
+    const RULE_CORPUS: &[(&str, Option<&str>)] = &[
+        (
+            "https://www.facebook.com/someone/posts/pfbid02abc",
+            Some("https://facebed.com/someone/posts/pfbid02abc"),
+        ),
+        ("https://www.facebook.com/", None),
+        (
+            "https://www.pixiv.net/en/artworks/12345678",
+            Some("https://phixiv.net/en/artworks/12345678"),
+        ),
+        (
+            "https://pixiv.net/artworks/12345678/2",
+            Some("https://phixiv.net/artworks/12345678/2"),
+        ),
+        ("https://www.pixiv.net/users/123456", None),
+        (
+            "https://www.pixiv.net/member_illust.php?illust_id=12345678",
+            Some("https://phixiv.net/member_illust.php?illust_id=12345678"),
+        ),
+        (
+            "https://pixiv.net/i/12345678",
+            Some("https://www.phixiv.net/i/12345678"),
+        ),
+        (
+            "https://twitter.com/someone/status/1234567890123456789",
+            Some("https://fixupx.com/someone/status/1234567890123456789"),
+        ),
+        (
+            "https://x.com/some_one/status/1234567890123456789/photo/1",
+            Some("https://fixupx.com/some_one/status/1234567890123456789"),
+        ),
+        ("https://x.com/someone", None),
+        (
+            "https://vt.tiktok.com/ZSabc123/",
+            Some("https://kktiktok.com/ZSabc123"),
+        ),
+        (
+            "https://vm.tiktok.com/ZMabc123/",
+            Some("https://kktiktok.com/ZMabc123"),
+        ),
+        (
+            "https://www.tiktok.com/@some.one/video/7312345678901234567?lang=en",
+            Some("https://kktiktok.com/@some.one/video/7312345678901234567"),
+        ),
+        (
+            "https://m.tiktok.com/@some_one/photo/7312345678901234567",
+            Some("https://kktiktok.com/@some_one/photo/7312345678901234567"),
+        ),
+        ("https://www.tiktok.com/@someone", None),
+        (
+            "https://www.reddit.com/r/anime/comments/1abc2de/some_title/",
+            Some("https://old.rxddit.com/r/anime/comments/1abc2de/some_title/"),
+        ),
+        ("https://www.reddit.com/", None),
+        ("https://notreddit.com/r/anime", None),
+        (
+            "https://redd.it/1abc2de",
+            Some("https://old.rxddit.com/comments/1abc2de"),
+        ),
+        (
+            "https://bsky.app/profile/someone.bsky.social/post/3kabc123xyz",
+            Some("https://fxbsky.app/profile/someone.bsky.social/post/3kabc123xyz"),
+        ),
+        (
+            "https://bsky.app/profile/did:plc:abc123/post/3kabc123xyz",
+            Some("https://fxbsky.app/profile/did:plc:abc123/post/3kabc123xyz"),
+        ),
+        ("https://bsky.app/profile/someone.bsky.social", None),
+        (
+            "https://www.instagram.com/p/C1a2B3c4D5e/",
+            Some("https://kkinstagram.com/p/C1a2B3c4D5e"),
+        ),
+        (
+            "https://instagram.com/reel/C1a2B3c4D5e/?igsh=abc",
+            Some("https://kkinstagram.com/reel/C1a2B3c4D5e"),
+        ),
+        (
+            "https://www.instagram.com/reels/C1a2B3c4D5e/",
+            Some("https://kkinstagram.com/reels/C1a2B3c4D5e"),
+        ),
+        ("https://www.instagram.com/someone/", None),
+        (
+            "https://www.threads.net/@someone.else/post/C1a2B3c4D5e",
+            Some("https://fixthreads.net/@someone.else/post/C1a2B3c4D5e"),
+        ),
+        (
+            "https://www.threads.com/@someone/post/C1a2B3c4D5e",
+            Some("https://fixthreads.net/@someone/post/C1a2B3c4D5e"),
+        ),
+        (
+            "https://www.tumblr.com/some-blog/712345678901234567",
+            Some("https://tpmblr.com/some-blog/712345678901234567"),
+        ),
+        (
+            "https://www.tumblr.com/some-blog/712345678901234567/a-post-slug",
+            Some("https://tpmblr.com/some-blog/712345678901234567"),
+        ),
+        ("https://www.tumblr.com/some-blog", None),
+        (
+            "https://some-blog.tumblr.com/post/712345678901234567",
+            Some("https://tpmblr.com/some-blog/712345678901234567"),
+        ),
+        (
+            "https://some-blog.tumblr.com/post/712345678901234567/a-post-slug",
+            Some("https://tpmblr.com/some-blog/712345678901234567"),
+        ),
+        (
+            "https://www.fanbox.cc/@creator/posts/1234567",
+            Some("https://fxfanbox.cc/@creator/posts/1234567"),
+        ),
+        (
+            "https://creator.fanbox.cc/posts/1234567",
+            Some("https://fxfanbox.cc/@creator/posts/1234567"),
+        ),
+        ("https://www.fanbox.cc/@creator", None),
+        ("https://example.com/", None),
+    ];
+
+    #[tokio::test]
+    async fn rewrites_the_link_corpus() {
+        let rules = seeded_rules().await;
+
+        for (link, expected) in RULE_CORPUS {
+            assert_eq!(
+                rewrite_links(&rules, link, |_| true),
+                expected
+                    .iter()
+                    .map(|url| url.to_string())
+                    .collect::<Vec<String>>(),
+                "{}",
+                link
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn every_seeded_rule_is_in_the_corpus() {
+        for rule in seeded_rules().await {
+            assert!(
+                RULE_CORPUS
+                    .iter()
+                    .any(|(link, _)| rule.regex.is_match(link).unwrap()),
+                "no link in the corpus matches the {} rule",
+                rule.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn matches_links_around_non_ascii_text() {
+        assert_eq!(
+            rewrite(
+                "🎉 ||ünï https://bsky.app/profile/someone.bsky.social/post/3kabc123xyz 🌸|| \
+                 `🚀 https://bsky.app/profile/someone.bsky.social/post/3kdef456uvw 🚀` \
+                 ✨ https://bsky.app/profile/someone.bsky.social/post/3kghi789rst 終わり"
+            )
+            .await,
+            vec![
+                "||https://fxbsky.app/profile/someone.bsky.social/post/3kabc123xyz||",
+                "https://fxbsky.app/profile/someone.bsky.social/post/3kghi789rst",
+            ]
+        );
+    }
+
+    const SHARE_LINK: &str = "https://www.reddit.com/r/anime/s/AbC123xyZ";
+    const POST_LINK: &str = "https://www.reddit.com/r/anime/comments/abc123/some_post/";
+
+    #[test]
+    fn only_accepts_redirects_to_reddit_posts() {
+        let url = |link: &str| reqwest::Url::parse(link).unwrap();
+
+        assert_eq!(
+            reddit_post_url(&url(
+                "https://old.reddit.com/r/anime/comments/abc123/some_post/?share_id=xyz"
+            )),
+            Some(POST_LINK.to_string())
+        );
+        assert_eq!(reddit_post_url(&url(SHARE_LINK)), None);
+        assert_eq!(
+            reddit_post_url(&url(
+                "https://www.reddit.com.example.com/r/anime/comments/abc123/"
+            )),
+            None
+        );
+    }
+
+    /// resolve the share links in `text`, counting how often `url` had to be looked up.
+    async fn resolve_counting(
+        share_links: &ShareLinks,
+        text: &str,
+        url: Option<&str>,
+        lookups: &std::sync::atomic::AtomicUsize,
+    ) -> Vec<(String, String)> {
+        resolve_share_links_with(share_links, text, |_| async move {
+            lookups.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            url.map(str::to_string)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn resolves_share_links_once() {
+        let share_links = ShareLinks::default();
+        let lookups = std::sync::atomic::AtomicUsize::new(0);
+        let text = format!("look 👀 {SHARE_LINK}");
+
+        for _ in 0..2 {
+            assert_eq!(
+                resolve_counting(&share_links, &text, Some(POST_LINK), &lookups).await,
+                vec![(SHARE_LINK.to_string(), POST_LINK.to_string())]
+            );
+        }
+
+        assert_eq!(lookups.into_inner(), 1);
+    }
+
+    #[tokio::test]
+    async fn leaves_unresolved_share_links_and_remembers_the_failure() {
+        let share_links = ShareLinks::default();
+        let lookups = std::sync::atomic::AtomicUsize::new(0);
+        let text = format!("look {SHARE_LINK}");
+
+        for _ in 0..2 {
+            assert!(
+                resolve_counting(&share_links, &text, None, &lookups)
+                    .await
+                    .is_empty()
+            );
+        }
+
+        assert_eq!(lookups.into_inner(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_share_links_once_their_failure_expires() {
+        let share_links = ShareLinks::default();
+        let lookups = std::sync::atomic::AtomicUsize::new(0);
+        let failed_at = Instant::now()
+            .checked_sub(FAILED_SHARE_LINK_CACHE_TTL + Duration::from_secs(1))
+            .unwrap();
+
+        share_links
+            .write()
+            .unwrap()
+            .insert(SHARE_LINK.to_string(), (failed_at, None));
+
+        assert_eq!(
+            resolve_counting(&share_links, SHARE_LINK, Some(POST_LINK), &lookups).await,
+            vec![(SHARE_LINK.to_string(), POST_LINK.to_string())]
+        );
+        assert_eq!(lookups.into_inner(), 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_share_links_as_posted() {
+        let rules = seeded_rules().await;
+        let rules = rules.iter().collect::<Vec<&CompiledEmbedRule>>();
+        let post = match_links(&rules, POST_LINK, &[], |_| true);
+        let shares = [(SHARE_LINK.to_string(), POST_LINK.to_string())];
+
+        assert_eq!(
+            match_links(
+                &rules,
+                &format!("look 👀 ||{SHARE_LINK}||"),
+                &shares,
+                |_| true
+            ),
+            vec![MatchedLink {
+                link: SHARE_LINK.to_string(),
+                url: post[0].url.clone(),
+                spoiler: true,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_next_healthy_host() {
+        let rules = seeded_rules().await;
+
+        assert_eq!(
+            rewrite_links(
+                &rules,
+                "https://bsky.app/profile/someone.bsky.social/post/3kabc123xyz",
+                |host| host != "fxbsky.app",
+            ),
+            vec!["https://bskx.app/profile/someone.bsky.social/post/3kabc123xyz"]
+        );
+        assert!(
+            rewrite_links(
+                &rules,
+                "https://www.threads.net/@someone/post/C1a2B3c4D5e",
+                |_| false,
+            )
+            .is_empty()
+        );
+    }
+}
@@ -1,105 +1,394 @@
-use std::sync::LazyLock;
-
-use anyhow::Result;
-use fancy_regex::{Captures, Regex};
+use anyhow::{Context as _, Result};
+use fancy_regex::Captures;
+use mangadex_api_schema_rust::v5::MangaAttributes;
+use mangadex_api_types_rust::ContentRating;
 use poise::serenity_prelude::{self as serenity, *};
 
 use crate::Data;
+use crate::bot_replies::{delete_reply, forget_reply, record_reply};
+use crate::commands::manga::TRACK_CUSTOM_ID_PREFIX;
+use crate::constants::music::SONGLINK_PLATFORMS;
+use crate::embed_controls::{get_embed_controls, is_opted_out};
+use crate::embed_rules::{
+    MatchedLink, is_host_healthy, match_links, resolve_share_links, rewritten_urls, tracked_links,
+};
+use crate::link_previews::{fetch_link_preview, preview_embed, truncate};
+use crate::link_shares::{find_earlier_share, record_link_shares};
+use crate::mangadex::MangaDexApi;
+use crate::models::bot_replies::{BotReply, ReplyKind};
+use crate::models::embeds::CompiledEmbedRule;
+use crate::models::link_previews::LinkPreview;
+use crate::models::settings::{AdultPreviewMode, LinkPreviewMode, LinkReplyMode};
 use crate::models::songlink::SonglinkResponse;
+use crate::pixiv::find_gallery;
+use crate::reposts::repost_message;
+use crate::settings::get_guild_settings;
+use crate::tracking_params::{clean_links, clean_url};
 
-type UrlBuilder = Box<dyn Fn(&Captures<'_>) -> String + Send + Sync>;
-
-pub static EMBED_BUILDERS: LazyLock<Vec<(&'static Regex, UrlBuilder)>> = LazyLock::new(|| {
-    use crate::constants::embeds::*;
-
-    vec![
-        (
-            &*FACEBOOK_URL_REGEX,
-            Box::new(|c: &Captures| format!("https://facebed.com/{}", &c[1])),
-        ),
-        (
-            &*PIXIV_ARTWORK_URL_REGEX,
-            Box::new(|c: &Captures| {
-                let lang = c.name("lang").map_or("", |s| s.as_str());
-                let id = c.name("id").map_or("", |s| s.as_str());
-                let idx = c.name("idx").map_or("", |s| s.as_str());
-
-                let path = if lang.is_empty() {
-                    format!("artworks/{id}")
-                } else {
-                    format!("{lang}/artworks/{id}")
-                };
-
-                let path = if !idx.is_empty() {
-                    format!("{path}/{idx}")
-                } else {
-                    path
-                };
-
-                format!("https://phixiv.net/{path}")
-            }),
-        ),
-        (
-            &*PIXIV_LEGACY_REGEX,
-            Box::new(|c: &Captures| {
-                format!("https://phixiv.net/member_illust.php?illust_id={}", &c[1])
-            }),
-        ),
-        (
-            &*PIXIV_SHORT_URL_REGEX,
-            Box::new(|c: &Captures| format!("https://www.phixiv.net/i/{}", &c[1])),
-        ),
-        (
-            &*TWITTER_URL_REGEX,
-            Box::new(|c: &Captures| format!("https://fixupx.com/{}/status/{}", &c[2], &c[3])),
-        ),
-        (
-            &*TIKTOK_URL_REGEX,
-            Box::new(|c: &Captures| format!("https://kktiktok.com/{}", &c[1])),
-        ),
-        (
-            &*REDDIT_URL_REGEX,
-            Box::new(|c: &Captures| format!("https://old.rxddit.com/{}", &c[1])),
-        ),
-    ]
-});
-
-async fn send_replacement_and_suppress(
-    ctx: &serenity::Context,
+/// discord's message length limit.
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// discord's limit on the length of an embed's description.
+const MAX_EMBED_DESCRIPTION_LENGTH: usize = 4096;
+
+/// discord allows 5 buttons in a row, and 5 rows in a message.
+const MAX_BUTTONS_PER_ROW: usize = 5;
+const MAX_LINK_BUTTONS: usize = 25;
+
+/// how many links get a native preview per message. discord caps the combined size of a
+/// message's embeds, so the rest go through the fixer hosts.
+const MAX_NATIVE_PREVIEWS: usize = 4;
+
+/// remember the links a message shared, if it was sent in a guild.
+async fn record_shared_links(
+    data: &Data,
     message: &Message,
-    replacement_url: String,
-) -> Result<()> {
-    message.reply(&ctx.http, replacement_url).await?;
-    message
-        .channel_id
-        .edit_message(
-            &ctx.http,
-            message.id,
-            EditMessage::new().suppress_embeds(true),
-        )
-        .await?;
+    shared_message_id: MessageId,
+    links: &[MatchedLink],
+) {
+    let Some(guild_id) = message.guild_id else {
+        return;
+    };
+
+    let links = links
+        .iter()
+        .map(|link| link.link.as_str())
+        .collect::<Vec<&str>>();
+
+    record_link_shares(
+        &data.db,
+        guild_id,
+        message.channel_id,
+        shared_message_id,
+        &links,
+    )
+    .await;
+}
 
-    Ok(())
+/// the text of a message with its links swapped for the rewritten ones, for reposting it. links
+/// with native previews are suppressed so discord doesn't preview them a second time.
+fn repost_content(text: &str, links: &[MatchedLink], previews: &[LinkPreview]) -> String {
+    let mut content = text.to_string();
+
+    for link in links {
+        if previews.iter().any(|preview| preview.url == link.link) {
+            content = content.replace(&link.link, &format!("<{}>", link.link));
+        } else if let Some(url) = link.url.as_ref().filter(|url| **url != link.link) {
+            content = content.replace(&link.link, url);
+        }
+    }
+
+    content
 }
 
+/// replies with the rewritten links in a message, or with previews built by the bot itself if the
+/// server uses native link previews. links no rule covers are offered without their tracking
+/// parameters, if they had any.
+///
+/// servers using the repost mode get new messages reposted with the links fixed instead, when
+/// the message can be. users who opted out, channels the guild turned rewriting off for and rules
+/// it disabled are skipped. links already shared in the channel within the guild's duplicate
+/// window get a jump link to the first post instead.
+///
+/// `existing` is the bot's previous embed reply when the message was edited. it is edited to
+/// match the new links, or deleted if there are none left.
 pub async fn embed_handler(
     ctx: &serenity::Context,
     data: &Data,
     new_message: &Message,
+    existing: Option<&BotReply>,
 ) -> Result<()> {
-    for (regex, build_url) in EMBED_BUILDERS.iter() {
-        if let Ok(Some(captures)) = regex.captures(&new_message.content) {
-            send_replacement_and_suppress(ctx, new_message, build_url(&captures)).await?;
+    if is_opted_out(data, new_message.author.id) {
+        return Ok(());
+    }
+
+    let (preview_mode, reply_mode, disabled_rules, duplicate_window) = match new_message.guild_id {
+        Some(guild_id) => {
+            let controls = get_embed_controls(data, guild_id).await?;
+
+            if !controls.is_channel_enabled(new_message.channel_id) {
+                return Ok(());
+            }
+
+            (
+                controls.settings.link_previews,
+                controls.settings.link_replies,
+                controls.disabled_rules,
+                controls.settings.duplicate_window,
+            )
+        }
+        None => (LinkPreviewMode::Proxy, LinkReplyMode::Reply, vec![], 0),
+    };
+
+    let tracked = tracked_links(&new_message.content);
+
+    // rewritten links shouldn't carry the tracking parameters of the originals either.
+    let text = clean_links(&new_message.content);
+
+    // share links are matched by the posts they point at, but kept as they were posted.
+    let shares = resolve_share_links(data, &text).await;
+
+    // match everything up front so the locks aren't held across the reply. rules whose fixer
+    // hosts are all down are skipped, leaving the original embed alone.
+    let (links, cleaned) = {
+        let health = data.fixer_health.read().unwrap();
+        let all_rules = data.embed_rules.read().unwrap();
+        let rules = all_rules
+            .iter()
+            .filter(|rule| !disabled_rules.contains(&rule.name))
+            .collect::<Vec<&CompiledEmbedRule>>();
+
+        let links = match_links(&rules, &text, &shares, |host| {
+            is_host_healthy(&health, host)
+        });
+
+        // the music handlers already answer music links with clean ones of their own.
+        let cleaned = if data.music_channel_id == Some(new_message.channel_id) {
+            vec![]
+        } else {
+            tracked
+                .into_iter()
+                .filter(|link| match_links(&rules, &link.link, &shares, |_| true).is_empty())
+                .collect::<Vec<MatchedLink>>()
+        };
+
+        (links, cleaned)
+    };
+
+    // links shared again point to their first post instead of being embedded a second time.
+    let mut already_posted = vec![];
+    let mut duplicates = vec![];
+
+    if duplicate_window > 0 {
+        for link in links.iter().chain(&cleaned) {
+            let Some(jump_link) = find_earlier_share(
+                &data.db,
+                new_message.channel_id,
+                new_message.id,
+                &link.link,
+                duplicate_window,
+            )
+            .await?
+            else {
+                continue;
+            };
+
+            duplicates.push(link.link.clone());
+
+            if !already_posted.contains(&jump_link) {
+                already_posted.push(jump_link);
+            }
+        }
+    }
+
+    let (links, cleaned) = (
+        links
+            .into_iter()
+            .filter(|link| !duplicates.contains(&link.link))
+            .collect::<Vec<MatchedLink>>(),
+        cleaned
+            .into_iter()
+            .filter(|link| !duplicates.contains(&link.link))
+            .collect::<Vec<MatchedLink>>(),
+    );
+
+    let shared = links
+        .iter()
+        .chain(&cleaned)
+        .cloned()
+        .collect::<Vec<MatchedLink>>();
+
+    // embeds can't be spoilered, so links in spoilers always go through the fixer hosts.
+    let previews = match preview_mode {
+        LinkPreviewMode::Native => futures::future::join_all(
+            links
+                .iter()
+                .filter(|link| !link.spoiler)
+                .take(MAX_NATIVE_PREVIEWS)
+                .map(|link| fetch_link_preview(&data.reqwest_client, &link.link)),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<LinkPreview>>(),
+        LinkPreviewMode::Proxy => vec![],
+    };
+
+    // multi-page pixiv posts get buttons on the reply to page through them. reposts keep their
+    // undo button instead.
+    let gallery = find_gallery(
+        &data.reqwest_client,
+        links
+            .iter()
+            .filter(|link| !previews.iter().any(|preview| preview.url == link.link)),
+    )
+    .await;
+
+    let components = gallery
+        .map(|gallery| gallery.components(new_message.author.id))
+        .unwrap_or_default();
+
+    let replacement_urls = rewritten_urls(
+        links
+            .iter()
+            .filter(|link| !previews.iter().any(|preview| preview.url == link.link))
+            .chain(&cleaned),
+    );
+
+    let mut content = String::new();
+
+    let notices = already_posted
+        .iter()
+        .map(|jump_link| format!("already posted: {}", jump_link));
+
+    for url in notices.chain(replacement_urls) {
+        if content.len() + url.len() + 1 > MAX_MESSAGE_LENGTH {
+            break;
+        }
+
+        if !content.is_empty() {
+            content.push('\n');
+        }
+
+        content.push_str(&url);
+    }
+
+    let embeds = previews
+        .iter()
+        .map(preview_embed)
+        .collect::<Vec<CreateEmbed>>();
+
+    // what the reply shows, so edits that don't change it can be skipped.
+    let shown = previews
+        .iter()
+        .map(|preview| preview.url.as_str())
+        .chain(content.lines())
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    match existing {
+        Some(reply) if reply.link == shown => {}
+        Some(reply) if shown.is_empty() => {
+            delete_reply(ctx, data, reply).await;
+        }
+        Some(reply) => {
+            let (Ok(channel_id), Ok(reply_id)) = (
+                reply.channel_id.parse::<u64>(),
+                reply.reply_message_id.parse::<u64>(),
+            ) else {
+                return Ok(());
+            };
+
+            let edited = ChannelId::new(channel_id)
+                .edit_message(
+                    &ctx.http,
+                    MessageId::new(reply_id),
+                    EditMessage::new()
+                        .content(&content)
+                        .embeds(embeds)
+                        .components(components),
+                )
+                .await
+                .inspect_err(
+                    |e| tracing::error!(err = ?e, "an error occurred when editing message"),
+                )?;
+
+            record_reply(&data.db, new_message, &edited, ReplyKind::Embed, &shown).await;
+            record_shared_links(data, new_message, new_message.id, &shared).await;
+        }
+        None if shown.is_empty() => {}
+        None => {
+            // notices about earlier posts don't belong in the reposted message itself.
+            if reply_mode == LinkReplyMode::Repost && already_posted.is_empty() {
+                let content = repost_content(&text, &links, &previews);
+
+                if content.len() <= MAX_MESSAGE_LENGTH
+                    && let Some(repost) =
+                        repost_message(ctx, data, new_message, &content, embeds.clone()).await?
+                {
+                    record_shared_links(data, new_message, repost.id, &shared).await;
+
+                    return Ok(());
+                }
+            }
+
+            let reply = new_message
+                .channel_id
+                .send_message(
+                    &ctx.http,
+                    CreateMessage::default()
+                        .reference_message(MessageReference::from(new_message))
+                        .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                        .content(&content)
+                        .embeds(embeds)
+                        .components(components),
+                )
+                .await
+                .inspect_err(
+                    |e| tracing::error!(err = ?e, "an error occurred when sending reply"),
+                )?;
+
+            record_reply(&data.db, new_message, &reply, ReplyKind::Embed, &shown).await;
+            record_shared_links(data, new_message, new_message.id, &shared).await;
+
+            new_message
+                .channel_id
+                .edit_message(
+                    &ctx.http,
+                    new_message.id,
+                    EditMessage::new().suppress_embeds(true),
+                )
+                .await?;
         }
     }
 
     Ok(())
 }
 
-pub async fn youtube_handler(
+/// the songlink api url looking up a music link, without the link's tracking parameters.
+pub fn songlink_url(link: &str) -> String {
+    let link = clean_url(link).unwrap_or_else(|| link.to_string());
+
+    format!(
+        "https://api.song.link/v1-alpha.1/links?url={}&userCountry=JP",
+        urlencoding::encode(&link)
+    )
+}
+
+/// the buttons songlink's links for a song are shown as, in the order of `SONGLINK_PLATFORMS`.
+fn songlink_buttons(res: &SonglinkResponse) -> Vec<CreateButton> {
+    let mut platforms = res
+        .links_by_platform
+        .iter()
+        .map(|(platform, link)| {
+            let known = SONGLINK_PLATFORMS
+                .iter()
+                .position(|(key, _)| key == platform);
+
+            (known, platform, link)
+        })
+        .collect::<Vec<_>>();
+
+    platforms.sort_by_key(|(known, platform, _)| (known.unwrap_or(usize::MAX), *platform));
+
+    platforms
+        .into_iter()
+        .take(MAX_LINK_BUTTONS)
+        .map(|(known, platform, link)| {
+            let label = known.map_or(platform.as_str(), |idx| SONGLINK_PLATFORMS[idx].1);
+
+            CreateButton::new_link(&link.url).label(label)
+        })
+        .collect()
+}
+
+/// looks a music link up on songlink and replies with buttons linking to the song on every
+/// platform it's on.
+pub async fn songlink_handler(
     ctx: &serenity::Context,
     data: &Data,
     new_message: &Message,
+    platform: &str,
     captures: Captures<'_>,
 ) -> Result<()> {
     let mut msg = new_message
@@ -109,123 +398,273 @@ pub async fn youtube_handler(
             CreateMessage::default()
                 .reference_message(MessageReference::from(new_message))
                 .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                .content("got a youtube link! attempting to match it with songlink..."),
+                .content(format!(
+                    "got your {} link! attempting to match it with songlink...",
+                    platform
+                )),
         )
         .await
         .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
 
-    let url_encoded = urlencoding::encode(&captures[0]);
+    record_reply(&data.db, new_message, &msg, ReplyKind::Music, &captures[0]).await;
 
-    let res = data
+    // songlink answers links it doesn't know with an error, which is no different from no match.
+    let res = match data
         .reqwest_client
-        .get(format!(
-            "https://api.song.link/v1-alpha.1/links?url={}&userCountry=JP",
-            url_encoded
-        ))
+        .get(songlink_url(&captures[0]))
         .send()
         .await
-        .inspect_err(
-            |e| tracing::error!(err = ?e, "an error occurred when fetching song from songlink"),
-        )?;
-
-    let res: SonglinkResponse = res.json().await.inspect_err(
-        |e| tracing::error!(err = ?e, "an error occurred when decoding songlink response"),
-    )?;
-
-    match res.links_by_platform.spotify {
-        Some(spotify) => {
-            msg.edit(
-                ctx,
-                EditMessage::default()
-                    .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                    .content(format!("here's your spotify link: {}", spotify.url)),
-            )
+        .and_then(|res| res.error_for_status())
+    {
+        Ok(res) => res
+            .json::<SonglinkResponse>()
             .await
-            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-        }
-        None => {
-            msg.edit(
-                ctx,
-                EditMessage::default()
-                    .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                    .content("i didn't match anything for your link..."),
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when decoding songlink response"),
             )
-            .await
-            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
-
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-
-            msg.delete(ctx).await?;
+            .ok(),
+        Err(e) => {
+            tracing::warn!(err = ?e, platform = %platform, "an error occurred when fetching song from songlink");
+            None
         }
-    }
+    };
 
-    Ok(())
-}
+    let buttons = res.as_ref().map(songlink_buttons).unwrap_or_default();
 
-pub async fn spotify_handler(
-    ctx: &serenity::Context,
-    data: &Data,
-    new_message: &Message,
-    captures: Captures<'_>,
-) -> Result<()> {
-    let mut msg = new_message
-        .channel_id
-        .send_message(
+    if buttons.is_empty() {
+        msg.edit(
             ctx,
-            CreateMessage::default()
-                .reference_message(MessageReference::from(new_message))
+            EditMessage::default()
                 .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                .content("got a spotify link! attempting to match it with songlink..."),
+                .content("i didn't match anything for your link..."),
         )
         .await
-        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
 
-    let url_encoded = urlencoding::encode(&captures[0]);
+        msg.delete(ctx).await?;
+        forget_reply(&data.db, msg.id).await;
+
+        return Ok(());
+    }
 
-    let res = data
+    let rows = buttons
+        .chunks(MAX_BUTTONS_PER_ROW)
+        .map(|row| CreateActionRow::Buttons(row.to_vec()))
+        .collect::<Vec<CreateActionRow>>();
+
+    msg.edit(
+        ctx,
+        EditMessage::default()
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+            .content("here's your song everywhere i could find it!")
+            .components(rows),
+    )
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+    Ok(())
+}
+
+async fn fetch_bytes(data: &Data, url: &str) -> Result<Vec<u8>> {
+    let bytes = data
         .reqwest_client
-        .get(format!(
-            "https://api.song.link/v1-alpha.1/links?url={}&userCountry=JP",
-            url_encoded
-        ))
+        .get(url)
         .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(bytes.to_vec())
+}
+
+/// the rich preview of a mangadex title shared by link previews and `s>manga random`.
+pub struct MangaPreview {
+    pub embed: CreateEmbed,
+    /// the spoilered cover, when the channel's preview mode calls for one.
+    pub cover: Option<CreateAttachment>,
+    pub buttons: Vec<CreateButton>,
+}
+
+/// builds the preview of a manga for a channel, fetching its statistics from mangadex.
+///
+/// adult titles outside of nsfw channels follow the guild's adult preview mode.
+pub async fn manga_preview(
+    ctx: &serenity::Context,
+    data: &Data,
+    api: &dyn MangaDexApi,
+    manga_id: uuid::Uuid,
+    manga: &MangaAttributes,
+    channel_id: ChannelId,
+    guild_id: Option<GuildId>,
+) -> Result<MangaPreview> {
+    let en_title = manga.title.get(&mangadex_api_types_rust::Language::English);
+
+    let title = match en_title {
+        Some(en_title) => en_title,
+        None => {
+            match manga
+                .title
+                .get(&mangadex_api_types_rust::Language::JapaneseRomanized)
+            {
+                Some(jp_ro) => jp_ro,
+                None => manga
+                    .title
+                    .get(&mangadex_api_types_rust::Language::Japanese)
+                    .unwrap(),
+            }
+        }
+    };
+
+    let tags = manga
+        .tags
+        .iter()
+        .map(|tag| {
+            tag.attributes
+                .name
+                .get(&mangadex_api_types_rust::Language::English)
+                .unwrap()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let is_adult = matches!(
+        manga.content_rating,
+        Some(ContentRating::Erotica | ContentRating::Pornographic)
+    );
+
+    let tags = match manga.content_rating {
+        Some(content_rating) => format!("**{}**, {}", content_rating, tags),
+        None => tags,
+    };
+
+    let statistics = api
+        .statistics(manga_id)
         .await
+        .map_err(|e| anyhow::anyhow!(e))
         .inspect_err(
-            |e| tracing::error!(err = ?e, "an error occurred when fetching song from songlink"),
+            |e| tracing::error!(err = ?e, uuid = %manga_id, "an error occurred when fetching manga stats"),
         )?;
 
-    let res: SonglinkResponse = res.json().await.inspect_err(
-        |e| tracing::error!(err = ?e, "an error occurred when decoding songlink response"),
-    )?;
+    let buttons = match &manga.links {
+        Some(links) => {
+            let mut result = vec![];
 
-    match res.links_by_platform.youtube_music {
-        Some(youtube_music) => {
-            msg.edit(
-                ctx,
-                EditMessage::default()
-                    .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                    .content(format!("here's your youtube link: {}", youtube_music.url)),
-            )
-            .await
-            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+            if let Some(anilist) = &links.anilist {
+                result.push(
+                    CreateButton::new_link(format!("https://anilist.co/manga/{anilist}"))
+                        .label("AniList")
+                        .emoji(EmojiId::new(1349211782287331398)),
+                );
+            }
+
+            if let Some(mal) = &links.my_anime_list {
+                result.push(
+                    CreateButton::new_link(mal.to_string())
+                        .label("MyAnimeList")
+                        .emoji(EmojiId::new(1349211802537562253)),
+                );
+            }
+
+            result
         }
-        None => {
-            msg.edit(
-                ctx,
-                EditMessage::default()
-                    .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
-                    .content("i didn't match anything for your link..."),
-            )
-            .await
-            .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+        None => vec![],
+    };
 
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    let preview_mode = match guild_id {
+        Some(guild_id) if is_adult => {
+            let is_nsfw_channel = channel_id
+                .to_channel(ctx)
+                .await
+                .map(|channel| channel.is_nsfw())
+                .inspect_err(
+                    |e| tracing::warn!(err = ?e, "an error occurred when fetching channel"),
+                )
+                .unwrap_or(false);
 
-            msg.delete(ctx).await?;
+            if is_nsfw_channel {
+                AdultPreviewMode::Show
+            } else {
+                get_guild_settings(&data.db, guild_id)
+                    .await?
+                    .adult_manga_preview
+            }
+        }
+        _ => AdultPreviewMode::Show,
+    };
+
+    let description = match manga
+        .description
+        .get(&mangadex_api_types_rust::Language::English)
+    {
+        // the spoiler markers count towards the limit too.
+        Some(d) if !d.is_empty() && preview_mode != AdultPreviewMode::Show => {
+            format!("||{}||", truncate(d, MAX_EMBED_DESCRIPTION_LENGTH - 4))
+        }
+        Some(d) => truncate(d, MAX_EMBED_DESCRIPTION_LENGTH),
+        None => String::new(),
+    };
+
+    let cover_url = format!("https://og.mangadex.org/og-image/manga/{}", manga_id);
+
+    let embed = CreateEmbed::default()
+        .title(title)
+        .url(format!("https://mangadex.org/title/{}", manga_id))
+        .description(description)
+        .field(
+            "publication",
+            match manga.year {
+                Some(year) => {
+                    format!("{}, {}", year, manga.status)
+                }
+                None => manga.status.to_string(),
+            },
+            true,
+        )
+        .field(
+            "statistics",
+            match statistics.rating {
+                Some(avg) => {
+                    format!("{} follows, {:.02} ☆", statistics.follows, avg)
+                }
+                None => statistics.follows.to_string(),
+            },
+            true,
+        )
+        .field("tags", tags, false);
+
+    let mut preview = MangaPreview {
+        embed,
+        cover: None,
+        buttons,
+    };
+
+    match preview_mode {
+        AdultPreviewMode::Show => {
+            preview.embed = preview.embed.image(cover_url);
+        }
+        AdultPreviewMode::Spoiler => {
+            // discord doesn't blur embed images, so the cover goes out as a spoilered
+            // attachment instead.
+            match fetch_bytes(data, &cover_url).await {
+                Ok(cover) => {
+                    preview.cover = Some(CreateAttachment::bytes(
+                        cover,
+                        format!("SPOILER_{}.png", manga_id),
+                    ));
+                }
+                Err(e) => {
+                    tracing::warn!(err = ?e, uuid = %manga_id, "an error occurred when fetching manga cover");
+                }
+            }
         }
+        AdultPreviewMode::Omit => {}
     }
 
-    Ok(())
+    Ok(preview)
 }
 
 pub async fn md_handler(
@@ -252,101 +691,33 @@ pub async fn md_handler(
                     |e| tracing::error!(err = ?e, "an error occurred when sending reply"),
                 )?;
 
+            record_reply(&data.db, new_message, &msg, ReplyKind::Manga, &captures[0]).await;
+
             // FIXME: better error handling here
             // this currently silently errors and hangs instead of returning - the message will just
             // hang at "fetching data...".
-            let manga = data
-                .md
-                .as_ref()
-                .unwrap()
-                .manga()
-                .id(uuid)
-                .get()
-                .send()
-                .await
-                .inspect_err(
-                    |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga"),
-                )?;
+            let api = data
+                .mangadex()
+                .context("mangadex client is not initialized")?;
 
-            let manga_id = manga.data.id;
-            let manga = manga.data.attributes;
-
-            let en_title = manga.title.get(&mangadex_api_types_rust::Language::English);
-
-            let title = match en_title {
-                Some(en_title) => en_title,
-                None => {
-                    match manga
-                        .title
-                        .get(&mangadex_api_types_rust::Language::JapaneseRomanized)
-                    {
-                        Some(jp_ro) => jp_ro,
-                        None => manga
-                            .title
-                            .get(&mangadex_api_types_rust::Language::Japanese)
-                            .unwrap(),
-                    }
-                }
-            };
-
-            let tags = manga
-                .tags
-                .iter()
-                .map(|tag| {
-                    tag.attributes
-                        .name
-                        .get(&mangadex_api_types_rust::Language::English)
-                        .unwrap()
-                        .to_string()
-                })
-                .collect::<Vec<String>>()
-                .join(", ");
-
-            let tags = match manga.content_rating {
-                Some(content_rating) => format!("**{}**, {}", content_rating, tags),
-                None => tags,
-            };
-
-            let statistics = data
-                .md
-                .as_ref()
-                .unwrap()
-                .statistics()
-                .manga()
-                .id(uuid)
-                .get()
-                .send()
+            let manga = api
+                .manga_attributes(uuid)
                 .await
+                .map_err(|e| anyhow::anyhow!(e))
                 .inspect_err(
-                    |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga stats"),
+                    |e| tracing::error!(err = ?e, uuid = %uuid, "an error occurred when fetching manga"),
                 )?;
 
-            let statistics = statistics.statistics.get(&uuid).unwrap();
-
-            let buttons = match manga.links {
-                Some(links) => {
-                    let mut result = vec![];
-
-                    if let Some(anilist) = links.anilist {
-                        result.push(
-                            CreateButton::new_link(format!("https://anilist.co/manga/{anilist}"))
-                                .label("AniList")
-                                .emoji(EmojiId::new(1349211782287331398)),
-                        );
-                    }
-
-                    if let Some(mal) = links.my_anime_list {
-                        result.push(
-                            CreateButton::new_link(mal.to_string())
-                                .label("MyAnimeList")
-                                .emoji(EmojiId::new(1349211802537562253)),
-                        );
-                    }
-
-                    result
-                }
-                None => vec![],
-            };
+            let preview = manga_preview(
+                ctx,
+                data,
+                &api,
+                uuid,
+                &manga,
+                new_message.channel_id,
+                new_message.guild_id,
+            )
+            .await?;
 
             new_message
                 .channel_id
@@ -357,53 +728,18 @@ pub async fn md_handler(
                 )
                 .await?;
 
-            let edit_msg = EditMessage::default()
+            let mut edit_msg = EditMessage::default()
                 .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
                 .content("here's your manga!")
-                .embed(
-                    CreateEmbed::default()
-                        .title(title)
-                        .url(format!("https://mangadex.org/title/{}", manga_id))
-                        .description(
-                            match manga
-                                .description
-                                .get(&mangadex_api_types_rust::Language::English)
-                            {
-                                Some(d) => d,
-                                None => "",
-                            },
-                        )
-                        .image(format!(
-                            "https://og.mangadex.org/og-image/manga/{}",
-                            manga_id
-                        ))
-                        .field(
-                            "publication",
-                            match manga.year {
-                                Some(year) => {
-                                    format!("{}, {}", year, manga.status)
-                                }
-                                None => manga.status.to_string(),
-                            },
-                            true,
-                        )
-                        .field(
-                            "statistics",
-                            match statistics.rating.bayesian {
-                                Some(avg) => {
-                                    format!("{} follows, {:.02} ☆", statistics.follows, avg)
-                                }
-                                None => statistics.follows.to_string(),
-                            },
-                            true,
-                        )
-                        .field("tags", tags, false),
-                );
-            let edit_msg = if !buttons.is_empty() {
-                edit_msg.components(vec![CreateActionRow::Buttons(buttons)])
-            } else {
-                edit_msg
-            };
+                .embed(preview.embed);
+
+            if let Some(cover) = preview.cover {
+                edit_msg = edit_msg.new_attachment(cover);
+            }
+
+            if !preview.buttons.is_empty() {
+                edit_msg = edit_msg.components(vec![CreateActionRow::Buttons(preview.buttons)]);
+            }
 
             msg.edit(ctx, edit_msg).await.inspect_err(
                 |e| tracing::error!(err = ?e, "an error occurred when editing message"),
@@ -418,6 +754,182 @@ pub async fn md_handler(
     Ok(())
 }
 
+/// sites whose manga links are resolved to mangadex titles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExternalMangaSite {
+    AniList,
+    MyAnimeList,
+}
+
+impl ExternalMangaSite {
+    fn name(self) -> &'static str {
+        match self {
+            ExternalMangaSite::AniList => "anilist",
+            ExternalMangaSite::MyAnimeList => "myanimelist",
+        }
+    }
+
+    /// the key of the site in mangadex's `links` and the manga search's link filters.
+    fn link_key(self) -> &'static str {
+        match self {
+            ExternalMangaSite::AniList => "al",
+            ExternalMangaSite::MyAnimeList => "mal",
+        }
+    }
+
+    /// whether a mangadex title links back to the entry with this id.
+    fn links_to(self, manga: &MangaAttributes, id: &str) -> bool {
+        let Some(links) = &manga.links else {
+            return false;
+        };
+
+        match self {
+            ExternalMangaSite::AniList => links
+                .anilist
+                .as_ref()
+                .is_some_and(|anilist| anilist.to_string() == id),
+            ExternalMangaSite::MyAnimeList => links.my_anime_list.as_ref().is_some_and(|mal| {
+                // mangadex stores either the bare id or a full link.
+                let mal = mal.to_string();
+                mal == id || mal.trim_end_matches('/').ends_with(&format!("/{id}"))
+            }),
+        }
+    }
+}
+
+/// finds the mangadex title linking to an anilist or myanimelist entry.
+///
+/// the manga search filters on the link already, but the results are checked against their links
+/// too, so an ignored filter can't turn into a wrong match.
+async fn find_external_manga(
+    api: &dyn MangaDexApi,
+    site: ExternalMangaSite,
+    id: &str,
+) -> Result<Option<(uuid::Uuid, MangaAttributes)>> {
+    let results = api
+        .manga_by_link(site.link_key(), id.to_string())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .inspect_err(
+            |e| tracing::error!(err = ?e, site = site.name(), id = %id, "an error occurred when searching manga"),
+        )?;
+
+    Ok(results
+        .into_iter()
+        .find(|(_, manga)| site.links_to(manga, id)))
+}
+
+pub async fn external_manga_handler(
+    ctx: &serenity::Context,
+    data: &Data,
+    new_message: &Message,
+    site: ExternalMangaSite,
+    captures: Captures<'_>,
+) -> Result<()> {
+    let id = &captures[1];
+
+    let mut msg = new_message
+        .channel_id
+        .send_message(
+            ctx,
+            CreateMessage::default()
+                .reference_message(MessageReference::from(new_message))
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content(format!(
+                    "got an {} link! looking for it on mangadex...",
+                    site.name()
+                )),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when sending reply"))?;
+
+    record_reply(&data.db, new_message, &msg, ReplyKind::Manga, &captures[0]).await;
+
+    let api = data
+        .mangadex()
+        .context("mangadex client is not initialized")?;
+
+    let Some((manga_id, manga)) = find_external_manga(&api, site, id).await? else {
+        msg.edit(
+            ctx,
+            EditMessage::default()
+                .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+                .content("i couldn't find this manga on mangadex..."),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+        msg.delete(ctx).await?;
+        forget_reply(&data.db, msg.id).await;
+
+        return Ok(());
+    };
+
+    let preview = manga_preview(
+        ctx,
+        data,
+        &api,
+        manga_id,
+        &manga,
+        new_message.channel_id,
+        new_message.guild_id,
+    )
+    .await?;
+
+    let hyphenated = manga_id.hyphenated();
+    let tracked = sqlx::query_scalar!(
+        r#"
+            SELECT
+                EXISTS (SELECT 1 FROM manga WHERE manga_dex_id = $1) AS "tracked!: bool";
+        "#,
+        hyphenated,
+    )
+    .fetch_one(&data.db)
+    .await
+    .inspect_err(|e| tracing::error!(err = ?e, uuid = %manga_id, "an error occurred when fetching manga from database"))?;
+
+    let mut buttons = preview.buttons;
+
+    if !tracked {
+        buttons.push(
+            CreateButton::new(format!("{}:{}", TRACK_CUSTOM_ID_PREFIX, manga_id))
+                .label("track")
+                .emoji('➕')
+                .style(ButtonStyle::Success),
+        );
+    }
+
+    new_message
+        .channel_id
+        .edit_message(
+            &ctx.http,
+            new_message.id,
+            EditMessage::new().suppress_embeds(true),
+        )
+        .await?;
+
+    let mut edit_msg = EditMessage::default()
+        .allowed_mentions(CreateAllowedMentions::new().replied_user(false))
+        .content("here's your manga!")
+        .embed(preview.embed);
+
+    if let Some(cover) = preview.cover {
+        edit_msg = edit_msg.new_attachment(cover);
+    }
+
+    if !buttons.is_empty() {
+        edit_msg = edit_msg.components(vec![CreateActionRow::Buttons(buttons)]);
+    }
+
+    msg.edit(ctx, edit_msg)
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when editing message"))?;
+
+    Ok(())
+}
+
 pub async fn quote_handler(
     ctx: &serenity::Context,
     data: &Data,
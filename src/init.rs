@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 
 use futures::StreamExt;
 use mangadex_api::MangaDexClient;
@@ -10,7 +11,16 @@ use sqlx::{Pool, Sqlite};
 use time::{Duration, OffsetDateTime, Time, UtcOffset};
 use tracing::Instrument;
 
+use crate::bot_replies::prune_old_replies;
 use crate::constants::version::get_log_version;
+use crate::embed_controls::{EmbedControls, EmbedOptouts, load_embed_optouts};
+use crate::embed_rules::{
+    EmbedRules, FIXER_HEALTH_CHECK_INTERVAL, FixerHealth, ShareLinks, check_fixer_hosts,
+    load_embed_rules,
+};
+use crate::link_shares::prune_old_link_shares;
+use crate::models::manga::TrackerSource;
+use crate::reposts::{Webhooks, prune_old_reposts};
 use crate::zenless::{self, ZenlessClient};
 use crate::{Data, chapter_tracker, commands, event_handler, gas_prices, telemetry};
 
@@ -163,6 +173,37 @@ fn init_mdlist_id() -> Option<uuid::Uuid> {
     mdlist_id
 }
 
+fn init_tracker_source() -> TrackerSource {
+    match std::env::var("MANGADEX_TRACKER_SOURCE").as_deref() {
+        Ok("follows") => {
+            tracing::info!("tracking new chapters from the mangadex user's followed manga feed.");
+            TrackerSource::Follows
+        }
+        Ok("list" | "") | Err(_) => TrackerSource::List,
+        Ok(source) => {
+            tracing::warn!(
+                "unknown MANGADEX_TRACKER_SOURCE {}, falling back to the manga list.",
+                source
+            );
+            TrackerSource::List
+        }
+    }
+}
+
+async fn init_embed_rules(db: &Pool<Sqlite>) -> anyhow::Result<EmbedRules> {
+    let rules = load_embed_rules(db).await?;
+    tracing::info!("loaded {} embed rules.", rules.len());
+
+    Ok(Arc::new(RwLock::new(rules)))
+}
+
+async fn init_embed_optouts(db: &Pool<Sqlite>) -> anyhow::Result<EmbedOptouts> {
+    let optouts = load_embed_optouts(db).await?;
+    tracing::info!("loaded {} embed opt-outs.", optouts.len());
+
+    Ok(Arc::new(RwLock::new(optouts)))
+}
+
 async fn init_discord_client(token: &str, data: Data) -> anyhow::Result<Client> {
     let intents =
         serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::MESSAGE_CONTENT;
@@ -178,6 +219,8 @@ async fn init_discord_client(token: &str, data: Data) -> anyhow::Result<Client>
                 commands::fluff::itl(),
                 commands::manga::manga(),
                 commands::quote::quote(),
+                commands::settings::settings(),
+                commands::embed::embed(),
             ],
             prefix_options: poise::PrefixFrameworkOptions {
                 prefix: Some("s>".into()),
@@ -221,6 +264,8 @@ fn spawn_background_tasks(client: &Client, data: &Data) {
     let md_data = data.clone();
     let gas_data = data.clone();
     let zzz_data = data.clone();
+    let fixer_data = data.clone();
+    let cleanup_db = data.db.clone();
 
     let md_http = client.http.clone();
     let gas_http = client.http.clone();
@@ -262,6 +307,37 @@ fn spawn_background_tasks(client: &Client, data: &Data) {
         .instrument(tracing::info_span!("gas_prices_task")),
     );
 
+    tokio::spawn(
+        async move {
+            let interval =
+                tokio::time::interval(std::time::Duration::from_secs(FIXER_HEALTH_CHECK_INTERVAL));
+            let task = futures::stream::unfold(interval, |mut interval| async {
+                interval.tick().await;
+
+                check_fixer_hosts(&fixer_data).await;
+                Some(((), interval))
+            });
+
+            task.for_each(|_| async {}).await;
+        }
+        .instrument(tracing::info_span!("fixer_health_task")),
+    );
+
+    tokio::spawn(
+        async move {
+            let interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            let task = futures::stream::unfold(interval, |mut interval| async {
+                interval.tick().await;
+
+                prune_old_rows(&cleanup_db).await;
+                Some(((), interval))
+            });
+
+            task.for_each(|_| async {}).await;
+        }
+        .instrument(tracing::info_span!("cleanup_task")),
+    );
+
     tokio::spawn(
         async move {
             let target_time = Time::MIDNIGHT;
@@ -296,6 +372,24 @@ fn spawn_background_tasks(client: &Client, data: &Data) {
     );
 }
 
+/// forget bot replies, link shares and reposts that are too old to matter anymore.
+async fn prune_old_rows(db: &Pool<Sqlite>) {
+    match prune_old_replies(db).await {
+        Ok(count) => tracing::info!(count, "pruned old bot replies"),
+        Err(e) => tracing::error!(err = ?e, "an error occurred when pruning bot replies"),
+    }
+
+    match prune_old_link_shares(db).await {
+        Ok(count) => tracing::info!(count, "pruned old link shares"),
+        Err(e) => tracing::error!(err = ?e, "an error occurred when pruning link shares"),
+    }
+
+    match prune_old_reposts(db).await {
+        Ok(count) => tracing::info!(count, "pruned old reposts"),
+        Err(e) => tracing::error!(err = ?e, "an error occurred when pruning reposts"),
+    }
+}
+
 pub async fn init() -> anyhow::Result<Client> {
     tracing::info!("initializing... please wait warmly.");
 
@@ -306,6 +400,13 @@ pub async fn init() -> anyhow::Result<Client> {
     let db = init_database().await?;
     let md = init_md().await?;
     let mdlist_id = init_mdlist_id();
+    let tracker_source = init_tracker_source();
+    let embed_rules = init_embed_rules(&db).await?;
+    let embed_controls = EmbedControls::default();
+    let embed_optouts = init_embed_optouts(&db).await?;
+    let fixer_health = FixerHealth::default();
+    let share_links = ShareLinks::default();
+    let webhooks = Webhooks::default();
     let (
         manga_update_channel_id,
         music_channel_id,
@@ -325,6 +426,13 @@ pub async fn init() -> anyhow::Result<Client> {
         db,
         md,
         mdlist_id,
+        tracker_source,
+        embed_rules,
+        embed_controls,
+        embed_optouts,
+        fixer_health,
+        share_links,
+        webhooks,
     };
 
     let client = init_discord_client(&token, data.clone()).await?;
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use poise::serenity_prelude::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter};
+use reqwest::Url;
+use reqwest::header::{CONTENT_TYPE, USER_AGENT};
+use scraper::{Html, Selector};
+
+use crate::models::link_previews::{LinkPreview, OEmbedResponse};
+
+/// sites are more likely to serve their metadata to a crawler they know is after a preview.
+const PREVIEW_USER_AGENT: &str =
+    "Mozilla/5.0 (compatible; Discordbot/2.0; +https://discordapp.com)";
+
+const PREVIEW_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// how much of a page is read looking for its metadata, which should be in the `<head>` anyway.
+const PREVIEW_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+const PREVIEW_MAX_TITLE_LENGTH: usize = 256;
+const PREVIEW_MAX_DESCRIPTION_LENGTH: usize = 350;
+
+/// `text` cut down to `max_length` characters, ending in an ellipsis if it was cut.
+pub fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let mut truncated = text.chars().take(max_length - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+/// parse a `#rrggbb` colour.
+fn parse_color(color: &str) -> Option<u32> {
+    let hex = color.trim().strip_prefix('#')?;
+
+    if hex.len() != 6 {
+        return None;
+    }
+
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// build a preview from a page's OpenGraph and twitter card meta tags, falling back to its
+/// `<title>` and description. relative urls are resolved against `url`.
+pub fn parse_link_preview(url: &Url, html: &str) -> LinkPreview {
+    let document = Html::parse_document(html);
+    let meta_selector = Selector::parse("meta[content]").unwrap();
+    let title_selector = Selector::parse("title").unwrap();
+    let oembed_selector = Selector::parse(r#"link[type="application/json+oembed"][href]"#).unwrap();
+
+    // the first non-empty value of each tag wins, like it does for discord's own previews.
+    let mut meta: HashMap<String, String> = HashMap::new();
+
+    for element in document.select(&meta_selector) {
+        let element = element.value();
+
+        let Some(key) = element.attr("property").or_else(|| element.attr("name")) else {
+            continue;
+        };
+
+        let content = element.attr("content").unwrap_or_default().trim();
+
+        if content.is_empty() {
+            continue;
+        }
+
+        meta.entry(key.to_lowercase())
+            .or_insert_with(|| content.to_string());
+    }
+
+    let get = |keys: &[&str]| keys.iter().find_map(|key| meta.get(*key).cloned());
+    let resolve = |link: &str| url.join(link).ok().map(|link| link.to_string());
+
+    let title = get(&["og:title", "twitter:title"]).or_else(|| {
+        document
+            .select(&title_selector)
+            .next()
+            .map(|title| title.text().collect::<String>().trim().to_string())
+            .filter(|title| !title.is_empty())
+    });
+
+    LinkPreview {
+        url: url.to_string(),
+        site_name: get(&["og:site_name", "application-name"]),
+        title,
+        description: get(&["og:description", "twitter:description", "description"]),
+        image: get(&[
+            "og:image:secure_url",
+            "og:image:url",
+            "og:image",
+            "twitter:image",
+            "twitter:image:src",
+        ])
+        .and_then(|image| resolve(&image)),
+        author: get(&["author", "twitter:creator"]),
+        color: get(&["theme-color"]).and_then(|color| parse_color(&color)),
+        oembed_url: document
+            .select(&oembed_selector)
+            .next()
+            .and_then(|link| link.value().attr("href"))
+            .and_then(resolve),
+    }
+}
+
+/// the final url and the start of the html of a page.
+async fn fetch_page(client: &reqwest::Client, link: &str) -> Option<(Url, String)> {
+    let mut res = client
+        .get(link)
+        .header(USER_AGENT, PREVIEW_USER_AGENT)
+        .timeout(PREVIEW_FETCH_TIMEOUT)
+        .send()
+        .await
+        .inspect_err(|e| tracing::warn!(err = ?e, link = %link, "an error occurred when fetching page for preview"))
+        .ok()?;
+
+    if !res.status().is_success() {
+        tracing::debug!(link = %link, status = %res.status(), "page for preview didn't load");
+        return None;
+    }
+
+    let is_html = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("html"));
+
+    if !is_html {
+        return None;
+    }
+
+    let url = res.url().clone();
+    let mut body = vec![];
+
+    while body.len() < PREVIEW_MAX_BODY_SIZE {
+        match res.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!(err = ?e, link = %link, "an error occurred when reading page for preview");
+                return None;
+            }
+        }
+    }
+
+    Some((url, String::from_utf8_lossy(&body).into_owned()))
+}
+
+async fn fetch_oembed(client: &reqwest::Client, url: &str) -> Option<OEmbedResponse> {
+    client
+        .get(url)
+        .header(USER_AGENT, PREVIEW_USER_AGENT)
+        .timeout(PREVIEW_FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .inspect_err(
+            |e| tracing::warn!(err = ?e, url = %url, "an error occurred when fetching oembed"),
+        )
+        .ok()?
+        .json()
+        .await
+        .inspect_err(
+            |e| tracing::warn!(err = ?e, url = %url, "an error occurred when decoding oembed"),
+        )
+        .ok()
+}
+
+/// fetch a page and build a preview from its metadata, using its oEmbed data to fill in the
+/// gaps. `None` if the page couldn't be fetched or has nothing worth showing.
+pub async fn fetch_link_preview(client: &reqwest::Client, link: &str) -> Option<LinkPreview> {
+    let (url, html) = fetch_page(client, link).await?;
+    let mut preview = parse_link_preview(&url, &html);
+    preview.url = link.to_string();
+
+    let is_incomplete =
+        preview.title.is_none() || preview.author.is_none() || preview.image.is_none();
+
+    let oembed = match preview.oembed_url.as_deref() {
+        Some(oembed_url) if is_incomplete => fetch_oembed(client, oembed_url).await,
+        _ => None,
+    };
+
+    if let Some(oembed) = oembed {
+        preview.merge_oembed(oembed);
+    }
+
+    (!preview.is_empty()).then_some(preview)
+}
+
+/// the embed the bot sends for a preview.
+pub fn preview_embed(preview: &LinkPreview) -> CreateEmbed {
+    let title = preview.title.as_deref().unwrap_or(&preview.url);
+
+    let mut embed = CreateEmbed::new()
+        .title(truncate(title, PREVIEW_MAX_TITLE_LENGTH))
+        .url(&preview.url);
+
+    if let Some(description) = &preview.description {
+        embed = embed.description(truncate(description, PREVIEW_MAX_DESCRIPTION_LENGTH));
+    }
+
+    if let Some(image) = &preview.image {
+        embed = embed.image(image);
+    }
+
+    if let Some(author) = &preview.author {
+        embed = embed.author(CreateEmbedAuthor::new(truncate(
+            author,
+            PREVIEW_MAX_TITLE_LENGTH,
+        )));
+    }
+
+    if let Some(site_name) = &preview.site_name {
+        embed = embed.footer(CreateEmbedFooter::new(truncate(
+            site_name,
+            PREVIEW_MAX_TITLE_LENGTH,
+        )));
+    }
+
+    if let Some(color) = preview.color {
+        embed = embed.colour(color);
+    }
+
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_fixture(url: &str, html: &str) -> LinkPreview {
+        parse_link_preview(&Url::parse(url).unwrap(), html)
+    }
+
+    #[test]
+    fn parses_opengraph_tags() {
+        let preview = parse_fixture(
+            "https://blog.example.com/posts/hello-world",
+            include_str!("../fixtures/link_previews/opengraph.html"),
+        );
+
+        assert_eq!(
+            preview,
+            LinkPreview {
+                url: "https://blog.example.com/posts/hello-world".to_string(),
+                site_name: Some("Example Blog".to_string()),
+                title: Some("Hello & welcome".to_string()),
+                description: Some("The first post on the example blog.".to_string()),
+                image: Some("https://blog.example.com/images/hello.png".to_string()),
+                author: Some("Sayaka Miki".to_string()),
+                color: Some(0x3366cc),
+                oembed_url: Some(
+                    "https://blog.example.com/oembed?url=https%3A%2F%2Fblog.example.com%2Fposts%2Fhello-world"
+                        .to_string()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_twitter_card_tags() {
+        let preview = parse_fixture(
+            "https://example.com/status/1",
+            include_str!("../fixtures/link_previews/twitter_card.html"),
+        );
+
+        assert_eq!(preview.title, Some("A card title".to_string()));
+        assert_eq!(preview.description, Some("A card description.".to_string()));
+        assert_eq!(
+            preview.image,
+            Some("https://cdn.example.com/card.jpg".to_string())
+        );
+        assert_eq!(preview.author, Some("@someone".to_string()));
+        assert_eq!(preview.site_name, None);
+        assert_eq!(preview.oembed_url, None);
+    }
+
+    #[test]
+    fn falls_back_to_the_page_title() {
+        let preview = parse_fixture(
+            "https://example.com/",
+            include_str!("../fixtures/link_previews/title_only.html"),
+        );
+
+        assert_eq!(preview.title, Some("Just a title".to_string()));
+        assert_eq!(
+            preview.description,
+            Some("A plain meta description.".to_string())
+        );
+        assert_eq!(preview.color, None);
+        assert!(!preview.is_empty());
+    }
+
+    #[test]
+    fn pages_without_metadata_are_empty() {
+        let preview = parse_fixture(
+            "https://example.com/",
+            include_str!("../fixtures/link_previews/empty.html"),
+        );
+
+        assert!(preview.is_empty());
+    }
+
+    #[test]
+    fn oembed_only_fills_in_missing_fields() {
+        let mut preview = parse_fixture(
+            "https://example.com/status/1",
+            include_str!("../fixtures/link_previews/twitter_card.html"),
+        );
+
+        let oembed: OEmbedResponse =
+            serde_json::from_str(include_str!("../fixtures/link_previews/oembed.json")).unwrap();
+
+        preview.merge_oembed(oembed);
+
+        assert_eq!(preview.title, Some("A card title".to_string()));
+        assert_eq!(preview.author, Some("@someone".to_string()));
+        assert_eq!(preview.site_name, Some("Example".to_string()));
+        assert_eq!(
+            preview.image,
+            Some("https://cdn.example.com/card.jpg".to_string())
+        );
+    }
+
+    #[test]
+    fn truncates_long_text() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a bit too long", 8), "a bit t…");
+    }
+}
@@ -0,0 +1,215 @@
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+
+use crate::constants::embeds::{HOST_ALIASES, MIRROR_SUBDOMAINS};
+use crate::models::settings::DEFAULT_DUPLICATE_WINDOW;
+use crate::tracking_params::clean_url;
+
+/// the form links are compared in: without the scheme, tracking parameters, fragment, trailing
+/// slash or mirror subdomains, and with aliased hosts under one name.
+pub fn normalize_url(link: &str) -> String {
+    let link = clean_url(link).unwrap_or_else(|| link.to_string());
+
+    let Ok(url) = Url::parse(&link) else {
+        return link;
+    };
+
+    let Some(mut host) = url.host_str() else {
+        return link;
+    };
+
+    for subdomain in MIRROR_SUBDOMAINS {
+        host = host.strip_prefix(subdomain).unwrap_or(host);
+    }
+
+    let host = HOST_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == host)
+        .map_or(host, |(_, canonical)| *canonical);
+
+    let mut normalized = format!("{}{}", host, url.path().trim_end_matches('/'));
+
+    if let Some(query) = url.query().filter(|query| !query.is_empty()) {
+        normalized.push('?');
+        normalized.push_str(query);
+    }
+
+    normalized
+}
+
+fn url_hash(link: &str) -> String {
+    format!("{:x}", Sha256::digest(normalize_url(link)))
+}
+
+/// remember that `links` were shared in a message, replacing what was recorded for it before since
+/// messages can be edited.
+///
+/// failures are only logged, since at worst a repeat goes unnoticed.
+pub async fn record_link_shares(
+    db: &Pool<Sqlite>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    links: &[&str],
+) {
+    forget_link_shares(db, message_id).await;
+
+    let guild_id = guild_id.to_string();
+    let channel_id = channel_id.to_string();
+    let message_id = message_id.to_string();
+    let now = time::OffsetDateTime::now_utc();
+    let created_at = time::OffsetDateTime::new_utc(now.date(), now.time());
+
+    for link in links {
+        let url_hash = url_hash(link);
+
+        let _ = sqlx::query!(
+            r#"
+                INSERT INTO
+                    link_shares (url_hash, guild_id, channel_id, message_id, created_at)
+                VALUES
+                    ($1, $2, $3, $4, $5);
+            "#,
+            url_hash,
+            guild_id,
+            channel_id,
+            message_id,
+            created_at,
+        )
+        .execute(db)
+        .await
+        .inspect_err(
+            |e| tracing::error!(err = ?e, message_id = %message_id, "an error occurred when recording link share"),
+        );
+    }
+}
+
+/// the jump link to the first message in a channel that shared `link` in the last
+/// `window_minutes`, other than `message_id` itself.
+pub async fn find_earlier_share(
+    db: &Pool<Sqlite>,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    link: &str,
+    window_minutes: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    let url_hash = url_hash(link);
+    let channel = channel_id.to_string();
+    let message = message_id.to_string();
+    let now = time::OffsetDateTime::now_utc();
+    let since = time::OffsetDateTime::new_utc(now.date(), now.time())
+        - time::Duration::minutes(window_minutes);
+
+    let share = sqlx::query!(
+        r#"
+            SELECT
+                guild_id,
+                message_id
+            FROM link_shares
+            WHERE channel_id = $1 AND url_hash = $2 AND message_id != $3 AND created_at >= $4
+            ORDER BY created_at
+            LIMIT 1;
+        "#,
+        channel,
+        url_hash,
+        message,
+        since,
+    )
+    .fetch_optional(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, channel_id = %channel_id, "an error occurred when fetching link share"),
+    )?;
+
+    Ok(share.and_then(|share| {
+        let guild_id = share.guild_id.parse::<u64>().ok()?;
+        let message_id = share.message_id.parse::<u64>().ok()?;
+
+        Some(MessageId::new(message_id).link(channel_id, Some(GuildId::new(guild_id))))
+    }))
+}
+
+/// stop tracking the links shared in a message, e.g. after it was deleted.
+pub async fn forget_link_shares(db: &Pool<Sqlite>, message_id: MessageId) {
+    let message_id = message_id.to_string();
+
+    let _ = sqlx::query!(
+        r#"
+            DELETE FROM link_shares
+            WHERE message_id = $1;
+        "#,
+        message_id,
+    )
+    .execute(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, message_id = %message_id, "an error occurred when forgetting link shares"),
+    );
+}
+
+/// forget shares that are older than every guild's duplicate window, returning how many were
+/// forgotten.
+pub async fn prune_old_link_shares(db: &Pool<Sqlite>) -> Result<u64, sqlx::Error> {
+    // guilds without settings use the default window.
+    let window = sqlx::query_scalar!(
+        r#"
+            SELECT MAX(duplicate_window) AS "window: i64"
+            FROM guild_settings;
+        "#
+    )
+    .fetch_one(db)
+    .await?
+    .unwrap_or_default()
+    .max(DEFAULT_DUPLICATE_WINDOW);
+
+    let now = time::OffsetDateTime::now_utc();
+    let cutoff =
+        time::OffsetDateTime::new_utc(now.date(), now.time()) - time::Duration::minutes(window);
+
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM link_shares
+            WHERE created_at < $1;
+        "#,
+        cutoff,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_equivalent_links_alike() {
+        assert_eq!(
+            normalize_url("https://twitter.com/user/status/1?s=20"),
+            "x.com/user/status/1"
+        );
+        assert_eq!(
+            normalize_url("http://www.x.com/user/status/1/#reply"),
+            "x.com/user/status/1"
+        );
+        assert_eq!(
+            normalize_url("https://old.reddit.com/r/rust/comments/abc/?utm_source=share"),
+            "reddit.com/r/rust/comments/abc"
+        );
+    }
+
+    #[test]
+    fn keeps_meaningful_query_parameters() {
+        assert_eq!(
+            normalize_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&si=abc"),
+            "youtube.com/watch?v=dQw4w9WgXcQ"
+        );
+        assert_ne!(
+            url_hash("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            url_hash("https://www.youtube.com/watch?v=jNQXAC9IVRw")
+        );
+    }
+}
@@ -1,11 +1,20 @@
 use constants::STARTUP_TIME;
-use constants::manga::MD_URL_REGEX;
-use constants::music::{SPOTIFY_URL_REGEX, YOUTUBE_URL_REGEX};
+use constants::manga::{ANILIST_MANGA_URL_REGEX, MAL_MANGA_URL_REGEX, MD_URL_REGEX};
+use constants::music::MUSIC_PLATFORMS;
 use mangadex_api::MangaDexClient;
 use poise::serenity_prelude::{self as serenity, *};
 use sqlx::{Pool, Sqlite};
 
-use crate::handlers::{embed_handler, md_handler, quote_handler, spotify_handler, youtube_handler};
+use crate::bot_replies::has_reply;
+use crate::embed_controls::{EmbedControls, EmbedOptouts};
+use crate::embed_rules::{EmbedRules, FixerHealth, ShareLinks};
+use crate::handlers::{
+    ExternalMangaSite, embed_handler, external_manga_handler, md_handler, quote_handler,
+    songlink_handler,
+};
+use crate::models::bot_replies::{BotReply, ReplyKind};
+use crate::models::manga::TrackerSource;
+use crate::reposts::Webhooks;
 use crate::zenless::ZenlessClient;
 
 #[derive(Clone)]
@@ -19,19 +28,46 @@ struct Data {
     db: Pool<Sqlite>,
     md: Option<MangaDexClient>,
     mdlist_id: Option<uuid::Uuid>,
+    tracker_source: TrackerSource,
+    embed_rules: EmbedRules,
+    embed_controls: EmbedControls,
+    embed_optouts: EmbedOptouts,
+    fixer_health: FixerHealth,
+    share_links: ShareLinks,
+    webhooks: Webhooks,
+}
+
+impl Data {
+    /// the mangadex api, or `None` when the client is not initialized.
+    fn mangadex(&self) -> Option<mangadex::LiveMangaDex<'_>> {
+        self.md
+            .as_ref()
+            .map(|md| mangadex::LiveMangaDex::new(md, &self.reqwest_client))
+    }
 }
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
+mod bot_replies;
 mod chapter_tracker;
 mod commands;
 mod constants;
+mod embed_controls;
+mod embed_rules;
 mod gas_prices;
 mod handlers;
 mod init;
+mod link_previews;
+mod link_shares;
+mod mangadex;
 mod models;
+mod paginator;
+mod pixiv;
+mod reposts;
+mod settings;
 mod telemetry;
+mod tracking_params;
 mod zenless;
 
 #[tracing::instrument(skip(ctx, _framework, data))]
@@ -41,38 +77,117 @@ async fn event_handler(
     _framework: poise::FrameworkContext<'_, Data, Error>,
     data: &Data,
 ) -> Result<(), Error> {
+    if let serenity::FullEvent::InteractionCreate { interaction } = event {
+        paginator::handle_interaction(
+            ctx,
+            data,
+            interaction,
+            &[commands::manga::MANGA_LIST, commands::quote::QUOTE_LIST],
+        )
+        .await?;
+        commands::manga::handle_random_interaction(ctx, data, interaction).await?;
+        commands::manga::handle_track_interaction(ctx, data, interaction).await?;
+        reposts::handle_undo_repost_interaction(ctx, data, interaction).await?;
+        pixiv::handle_gallery_interaction(ctx, interaction).await?;
+    }
+
     if let serenity::FullEvent::Message { new_message } = event {
         if new_message.author.bot || new_message.content.starts_with("s>") {
             return Ok(());
         }
 
-        if let Some(music_channel_id) = data.music_channel_id
-            && new_message.channel_id == music_channel_id
-        {
-            if let Ok(Some(captures)) = YOUTUBE_URL_REGEX.captures(&new_message.content) {
-                youtube_handler(ctx, data, new_message, captures).await?;
-            }
+        link_handlers(ctx, data, new_message, &[]).await?;
+
+        if new_message.content.starts_with("... ") {
+            quote_handler(ctx, data, new_message).await?;
+        }
+    }
+
+    if let serenity::FullEvent::MessageUpdate { new, event, .. } = event
+        && let Some(message) = bot_replies::edited_message(ctx, new.as_ref(), event).await?
+    {
+        let replies = bot_replies::prune_replies(ctx, data, &message).await?;
+
+        if !message.content.starts_with("s>") {
+            link_handlers(ctx, data, &message, &replies).await?;
+        }
+    }
+
+    if let serenity::FullEvent::MessageDelete {
+        deleted_message_id, ..
+    } = event
+    {
+        bot_replies::delete_replies(ctx, data, *deleted_message_id).await?;
+        reposts::forget_repost(&data.db, *deleted_message_id).await;
+        link_shares::forget_link_shares(&data.db, *deleted_message_id).await;
+    }
 
-            if let Ok(Some(captures)) = SPOTIFY_URL_REGEX.captures(&new_message.content) {
-                spotify_handler(ctx, data, new_message, captures).await?;
+    if let serenity::FullEvent::MessageDeleteBulk {
+        multiple_deleted_messages_ids,
+        ..
+    } = event
+    {
+        for message_id in multiple_deleted_messages_ids {
+            bot_replies::delete_replies(ctx, data, *message_id).await?;
+            reposts::forget_repost(&data.db, *message_id).await;
+            link_shares::forget_link_shares(&data.db, *message_id).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// runs the link handlers on a message.
+///
+/// `replies` are the bot's existing replies to the message when it was edited, so links that
+/// were already answered aren't answered again.
+async fn link_handlers(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &Message,
+    replies: &[BotReply],
+) -> Result<(), Error> {
+    if let Some(music_channel_id) = data.music_channel_id
+        && message.channel_id == music_channel_id
+    {
+        for (platform, regex) in MUSIC_PLATFORMS {
+            if let Ok(Some(captures)) = regex.captures(&message.content)
+                && !has_reply(replies, ReplyKind::Music, &captures[0])
+            {
+                songlink_handler(ctx, data, message, platform, captures).await?;
             }
         }
+    }
 
-        if let Ok(Some(captures)) = MD_URL_REGEX.captures(&new_message.content)
-            && let Some(manga_channel_id) = data.manga_update_channel_id
-            && new_message.channel_id == manga_channel_id
-            && data.md.is_some()
+    if let Some(manga_channel_id) = data.manga_update_channel_id
+        && message.channel_id == manga_channel_id
+        && data.md.is_some()
+    {
+        if let Ok(Some(captures)) = MD_URL_REGEX.captures(&message.content)
+            && !has_reply(replies, ReplyKind::Manga, &captures[0])
         {
-            md_handler(ctx, data, new_message, captures).await?;
+            md_handler(ctx, data, message, captures).await?;
         }
 
-        embed_handler(ctx, data, new_message).await?;
+        if let Ok(Some(captures)) = ANILIST_MANGA_URL_REGEX.captures(&message.content)
+            && !has_reply(replies, ReplyKind::Manga, &captures[0])
+        {
+            external_manga_handler(ctx, data, message, ExternalMangaSite::AniList, captures)
+                .await?;
+        }
 
-        if new_message.content.starts_with("... ") {
-            quote_handler(ctx, data, new_message).await?;
+        if let Ok(Some(captures)) = MAL_MANGA_URL_REGEX.captures(&message.content)
+            && !has_reply(replies, ReplyKind::Manga, &captures[0])
+        {
+            external_manga_handler(ctx, data, message, ExternalMangaSite::MyAnimeList, captures)
+                .await?;
         }
     }
 
+    let embed_reply = replies.iter().find(|reply| reply.kind == ReplyKind::Embed);
+
+    embed_handler(ctx, data, message, embed_reply).await?;
+
     Ok(())
 }
 
@@ -0,0 +1,553 @@
+use futures::future::BoxFuture;
+use mangadex_api::MangaDexClient;
+use mangadex_api_schema_rust::v5::{ChapterObject, MangaAttributes, RelatedAttributes};
+use mangadex_api_types_rust::{
+    Language, MangaDexDateTime, MangaFeedSortOrder, OrderDirection, ReferenceExpansionResource,
+    RelationshipType, ResultType,
+};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::Error;
+use crate::constants::manga::{MD_API_URL, MD_BLOCKED_LIST};
+use crate::models::external_manga::MangaSearchResponse;
+
+/// the parts of a manga the tracker cares about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MangaInfo {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub status: String,
+    pub demographic: Option<String>,
+    /// english tag names.
+    pub tags: Vec<String>,
+}
+
+/// the parts of a chapter the tracker cares about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChapterInfo {
+    pub id: Uuid,
+    pub manga_id: Uuid,
+    /// only filled in when the manga was requested as a reference expansion.
+    pub manga: Option<MangaInfo>,
+    pub chapter: Option<String>,
+    pub volume: Option<String>,
+    pub title: Option<String>,
+    pub pages: u32,
+    pub language: String,
+    pub publish_at: Option<OffsetDateTime>,
+    /// scanlation groups as `(id, name)`.
+    pub groups: Vec<(Uuid, String)>,
+    /// uploader as `(id, username)`.
+    pub uploader: Option<(Uuid, String)>,
+}
+
+impl ChapterInfo {
+    /// whether the chapter was uploaded by one of the groups in [`MD_BLOCKED_LIST`].
+    pub fn is_blocked(&self) -> bool {
+        self.groups
+            .iter()
+            .any(|(id, _)| MD_BLOCKED_LIST.contains(id))
+    }
+}
+
+/// the parts of a manga's statistics shown in previews.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MangaStats {
+    pub rating: Option<f64>,
+    pub follows: u64,
+}
+
+/// one page of the followed manga feed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeedPage {
+    pub chapters: Vec<ChapterInfo>,
+    pub total: u32,
+}
+
+/// the mangadex calls made by the chapter tracker and the manga commands.
+///
+/// [`LiveMangaDex`] talks to the real api. tests use [`fake::FakeMangaDex`] instead.
+pub trait MangaDexApi: Send + Sync {
+    fn manga(&self, id: Uuid) -> BoxFuture<'_, Result<MangaInfo, Error>>;
+
+    /// the full attributes of a manga, for embeds that show more than [`MangaInfo`] has.
+    fn manga_attributes(&self, id: Uuid) -> BoxFuture<'_, Result<MangaAttributes, Error>>;
+
+    fn statistics(&self, id: Uuid) -> BoxFuture<'_, Result<MangaStats, Error>>;
+
+    /// the highest numbered english chapter of a manga, only counting chapters published since
+    /// `since` if it is set.
+    fn latest_chapter(
+        &self,
+        manga_id: Uuid,
+        since: Option<OffsetDateTime>,
+    ) -> BoxFuture<'_, Result<Option<ChapterInfo>, Error>>;
+
+    /// english chapters of the logged-in user's followed manga published since `since`, newest
+    /// first.
+    fn follows_feed(
+        &self,
+        since: OffsetDateTime,
+        offset: u32,
+        limit: u32,
+    ) -> BoxFuture<'_, Result<FeedPage, Error>>;
+
+    fn refresh_token(&self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// follows or unfollows a manga on the logged-in account.
+    fn set_followed(&self, id: Uuid, follow: bool) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// manga linking to an entry on another site, found with the manga search's link filters.
+    /// `site` is mangadex's key for the site, like `al` for anilist.
+    fn manga_by_link(
+        &self,
+        site: &'static str,
+        id: String,
+    ) -> BoxFuture<'_, Result<Vec<(Uuid, MangaAttributes)>, Error>>;
+
+    /// replaces the contents of a custom list with `manga_ids`.
+    fn replace_custom_list(
+        &self,
+        list_id: Uuid,
+        manga_ids: Vec<Uuid>,
+    ) -> BoxFuture<'_, Result<(), Error>>;
+}
+
+pub struct LiveMangaDex<'a> {
+    client: &'a MangaDexClient,
+    /// for the few endpoints the client doesn't cover.
+    http: &'a reqwest::Client,
+}
+
+impl<'a> LiveMangaDex<'a> {
+    pub fn new(client: &'a MangaDexClient, http: &'a reqwest::Client) -> Self {
+        LiveMangaDex { client, http }
+    }
+}
+
+pub fn manga_title(manga: &MangaAttributes) -> Option<String> {
+    manga
+        .title
+        .get(&Language::English)
+        .or_else(|| manga.title.get(&Language::JapaneseRomanized))
+        .or_else(|| manga.title.get(&Language::Japanese))
+        .cloned()
+}
+
+pub fn manga_info(id: Uuid, manga: &MangaAttributes) -> MangaInfo {
+    MangaInfo {
+        id,
+        title: manga_title(manga),
+        status: manga.status.to_string(),
+        demographic: manga
+            .publication_demographic
+            .as_ref()
+            .map(|demographic| demographic.to_string()),
+        tags: manga
+            .tags
+            .iter()
+            .filter_map(|tag| tag.attributes.name.get(&Language::English).cloned())
+            .collect(),
+    }
+}
+
+fn chapter_info(chapter: &ChapterObject) -> ChapterInfo {
+    let chapter_data = &chapter.attributes;
+
+    let mut manga_id = Uuid::nil();
+    let mut manga = None;
+    let mut groups = vec![];
+    let mut uploader = None;
+
+    for relationship in &chapter.relationships {
+        match (&relationship.type_, &relationship.attributes) {
+            (RelationshipType::Manga, attributes) => {
+                manga_id = relationship.id;
+
+                if let Some(RelatedAttributes::Manga(attributes)) = attributes {
+                    manga = Some(manga_info(relationship.id, attributes));
+                }
+            }
+            (RelationshipType::ScanlationGroup, attributes) => {
+                let name = match attributes {
+                    Some(RelatedAttributes::ScanlationGroup(group)) => group.name.clone(),
+                    _ => "unknown".to_string(),
+                };
+
+                groups.push((relationship.id, name));
+            }
+            (RelationshipType::User, Some(RelatedAttributes::User(user))) => {
+                uploader = Some((relationship.id, user.username.clone()));
+            }
+            _ => {}
+        }
+    }
+
+    ChapterInfo {
+        id: chapter.id,
+        manga_id,
+        manga,
+        chapter: chapter_data.chapter.clone(),
+        volume: chapter_data.volume.clone(),
+        title: chapter_data.title.clone(),
+        pages: chapter_data.pages,
+        language: format!("{:?}", chapter_data.translated_language),
+        publish_at: chapter_data.publish_at.map(|timestamp| {
+            OffsetDateTime::new_utc(timestamp.as_ref().date(), timestamp.as_ref().time())
+        }),
+        groups,
+        uploader,
+    }
+}
+
+impl MangaDexApi for LiveMangaDex<'_> {
+    fn manga(&self, id: Uuid) -> BoxFuture<'_, Result<MangaInfo, Error>> {
+        Box::pin(async move {
+            let manga = self.manga_attributes(id).await?;
+
+            Ok(manga_info(id, &manga))
+        })
+    }
+
+    fn manga_attributes(&self, id: Uuid) -> BoxFuture<'_, Result<MangaAttributes, Error>> {
+        Box::pin(async move {
+            let manga = self.client.manga().id(id).get().send().await?;
+
+            Ok(manga.data.attributes)
+        })
+    }
+
+    fn statistics(&self, id: Uuid) -> BoxFuture<'_, Result<MangaStats, Error>> {
+        Box::pin(async move {
+            let statistics = self.client.statistics().manga().id(id).get().send().await?;
+            let statistics = statistics
+                .statistics
+                .get(&id)
+                .ok_or("mangadex returned no statistics for the manga")?;
+
+            Ok(MangaStats {
+                rating: statistics.rating.bayesian.map(f64::from),
+                follows: u64::from(statistics.follows),
+            })
+        })
+    }
+
+    fn latest_chapter(
+        &self,
+        manga_id: Uuid,
+        since: Option<OffsetDateTime>,
+    ) -> BoxFuture<'_, Result<Option<ChapterInfo>, Error>> {
+        Box::pin(async move {
+            let mut builder = self.client.manga().id(manga_id).feed().get();
+
+            if let Some(since) = since {
+                builder.publish_at_since(MangaDexDateTime::new(&since));
+            }
+
+            let feed = builder
+                .add_translated_language(&Language::English)
+                .order(MangaFeedSortOrder::Chapter(OrderDirection::Descending))
+                .excluded_groups(MD_BLOCKED_LIST.clone())
+                .include(&ReferenceExpansionResource::ScanlationGroup)
+                .include(&ReferenceExpansionResource::User)
+                .limit(1u32)
+                .send()
+                .await?;
+
+            if feed.result != ResultType::Ok {
+                return Ok(None);
+            }
+
+            Ok(feed.data.first().map(chapter_info))
+        })
+    }
+
+    fn follows_feed(
+        &self,
+        since: OffsetDateTime,
+        offset: u32,
+        limit: u32,
+    ) -> BoxFuture<'_, Result<FeedPage, Error>> {
+        Box::pin(async move {
+            let page = self
+                .client
+                .user()
+                .follows()
+                .manga()
+                .feed()
+                .get()
+                .add_translated_language(&Language::English)
+                .publish_at_since(MangaDexDateTime::new(&since))
+                .order(MangaFeedSortOrder::PublishAt(OrderDirection::Descending))
+                .include(&ReferenceExpansionResource::Manga)
+                .include(&ReferenceExpansionResource::ScanlationGroup)
+                .include(&ReferenceExpansionResource::User)
+                .limit(limit)
+                .offset(offset)
+                .send()
+                .await?;
+
+            Ok(FeedPage {
+                chapters: page.data.iter().map(chapter_info).collect(),
+                total: page.total,
+            })
+        })
+    }
+
+    fn refresh_token(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            self.client.oauth().refresh().send().await?;
+
+            Ok(())
+        })
+    }
+
+    fn set_followed(&self, id: Uuid, follow: bool) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let endpoint = self.client.manga().id(id).follow();
+
+            if follow {
+                endpoint.post().send().await?;
+            } else {
+                endpoint.delete().send().await?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn manga_by_link(
+        &self,
+        site: &'static str,
+        id: String,
+    ) -> BoxFuture<'_, Result<Vec<(Uuid, MangaAttributes)>, Error>> {
+        Box::pin(async move {
+            let res: MangaSearchResponse = self
+                .http
+                .get(format!("{}/manga", MD_API_URL))
+                .query(&[
+                    (format!("links[{}]", site), id),
+                    ("limit".to_string(), "10".to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            Ok(res
+                .data
+                .into_iter()
+                .filter_map(|manga| Some((Uuid::try_parse(&manga.id).ok()?, manga.attributes)))
+                .collect())
+        })
+    }
+
+    fn replace_custom_list(
+        &self,
+        list_id: Uuid,
+        manga_ids: Vec<Uuid>,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let list = self.client.custom_list().id(list_id).get().send().await?;
+
+            let mut builder = self.client.custom_list().id(list_id).put();
+
+            for manga_id in manga_ids {
+                builder.add_manga_id(manga_id);
+            }
+
+            builder
+                .version(list.data.attributes.version)
+                .build()?
+                .send()
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod fake {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// canned mangadex responses for tests.
+    #[derive(Default)]
+    pub struct FakeMangaDex {
+        pub manga: HashMap<Uuid, MangaInfo>,
+        /// full attributes, for the few callers that need more than `manga`.
+        pub attributes: HashMap<Uuid, MangaAttributes>,
+        pub statistics: HashMap<Uuid, MangaStats>,
+        /// the chapter returned by `latest_chapter`, regardless of `since`.
+        pub latest_chapters: HashMap<Uuid, ChapterInfo>,
+        /// the whole followed manga feed, newest first.
+        pub follows: Vec<ChapterInfo>,
+        /// manga linking to other sites, by `(site, id)`. their attributes come from `attributes`.
+        pub links: HashMap<(&'static str, String), Uuid>,
+        /// manga ids whose lookups fail.
+        pub failing: HashSet<Uuid>,
+        pub follows_fail: bool,
+        /// manga currently followed through `set_followed`.
+        pub followed: Mutex<HashSet<Uuid>>,
+        /// the last contents written by `replace_custom_list`, by list id.
+        pub custom_lists: Mutex<HashMap<Uuid, Vec<Uuid>>>,
+    }
+
+    impl FakeMangaDex {
+        pub fn with_manga(mut self, id: Uuid, title: &str) -> Self {
+            self.manga.insert(id, manga_info(id, title));
+            self
+        }
+    }
+
+    /// an ongoing manga with an english title.
+    pub fn manga_info(id: Uuid, title: &str) -> MangaInfo {
+        MangaInfo {
+            id,
+            title: Some(title.to_string()),
+            status: "ongoing".to_string(),
+            demographic: None,
+            tags: vec![],
+        }
+    }
+
+    /// a numbered chapter of `manga_id` with sensible defaults.
+    pub fn chapter(manga_id: Uuid, number: &str) -> ChapterInfo {
+        ChapterInfo {
+            id: Uuid::from_u128(rand::random()),
+            manga_id,
+            manga: None,
+            chapter: Some(number.to_string()),
+            volume: None,
+            title: None,
+            pages: 20,
+            language: "English".to_string(),
+            publish_at: Some(OffsetDateTime::now_utc()),
+            groups: vec![(Uuid::from_u128(rand::random()), "some group".to_string())],
+            uploader: None,
+        }
+    }
+
+    impl MangaDexApi for FakeMangaDex {
+        fn manga(&self, id: Uuid) -> BoxFuture<'_, Result<MangaInfo, Error>> {
+            Box::pin(async move {
+                if self.failing.contains(&id) {
+                    return Err("manga lookup failed".into());
+                }
+
+                self.manga
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| "manga not found".into())
+            })
+        }
+
+        fn manga_attributes(&self, id: Uuid) -> BoxFuture<'_, Result<MangaAttributes, Error>> {
+            Box::pin(async move {
+                if self.failing.contains(&id) {
+                    return Err("manga lookup failed".into());
+                }
+
+                self.attributes
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| "manga not found".into())
+            })
+        }
+
+        fn statistics(&self, id: Uuid) -> BoxFuture<'_, Result<MangaStats, Error>> {
+            Box::pin(async move { Ok(self.statistics.get(&id).cloned().unwrap_or_default()) })
+        }
+
+        fn latest_chapter(
+            &self,
+            manga_id: Uuid,
+            _since: Option<OffsetDateTime>,
+        ) -> BoxFuture<'_, Result<Option<ChapterInfo>, Error>> {
+            Box::pin(async move {
+                if self.failing.contains(&manga_id) {
+                    return Err("feed lookup failed".into());
+                }
+
+                Ok(self.latest_chapters.get(&manga_id).cloned())
+            })
+        }
+
+        fn follows_feed(
+            &self,
+            _since: OffsetDateTime,
+            offset: u32,
+            limit: u32,
+        ) -> BoxFuture<'_, Result<FeedPage, Error>> {
+            Box::pin(async move {
+                if self.follows_fail {
+                    return Err("follows feed lookup failed".into());
+                }
+
+                Ok(FeedPage {
+                    chapters: self
+                        .follows
+                        .iter()
+                        .skip(offset as usize)
+                        .take(limit as usize)
+                        .cloned()
+                        .collect(),
+                    total: self.follows.len() as u32,
+                })
+            })
+        }
+
+        fn refresh_token(&self) -> BoxFuture<'_, Result<(), Error>> {
+            Box::pin(async move { Ok(()) })
+        }
+
+        fn set_followed(&self, id: Uuid, follow: bool) -> BoxFuture<'_, Result<(), Error>> {
+            Box::pin(async move {
+                if self.failing.contains(&id) {
+                    return Err("follow failed".into());
+                }
+
+                let mut followed = self.followed.lock().unwrap();
+                if follow {
+                    followed.insert(id);
+                } else {
+                    followed.remove(&id);
+                }
+
+                Ok(())
+            })
+        }
+
+        fn manga_by_link(
+            &self,
+            site: &'static str,
+            id: String,
+        ) -> BoxFuture<'_, Result<Vec<(Uuid, MangaAttributes)>, Error>> {
+            Box::pin(async move {
+                Ok(self
+                    .links
+                    .get(&(site, id))
+                    .and_then(|manga_id| Some((*manga_id, self.attributes.get(manga_id)?.clone())))
+                    .into_iter()
+                    .collect())
+            })
+        }
+
+        fn replace_custom_list(
+            &self,
+            list_id: Uuid,
+            manga_ids: Vec<Uuid>,
+        ) -> BoxFuture<'_, Result<(), Error>> {
+            Box::pin(async move {
+                self.custom_lists.lock().unwrap().insert(list_id, manga_ids);
+
+                Ok(())
+            })
+        }
+    }
+}
@@ -1,5 +1,11 @@
+pub mod bot_replies;
+pub mod embeds;
+pub mod external_manga;
 pub mod gas_prices;
+pub mod link_previews;
 pub mod manga;
+pub mod pixiv;
 pub mod quotes;
+pub mod settings;
 pub mod songlink;
 pub mod zenless;
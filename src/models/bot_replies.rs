@@ -0,0 +1,23 @@
+/// what a bot reply was sent for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub enum ReplyKind {
+    /// rewritten links from the embed rules.
+    Embed,
+    /// songlink matches for links to any of the music platforms.
+    Music,
+    /// mangadex, anilist and myanimelist previews.
+    Manga,
+}
+
+/// a reply the bot sent in response to a link in someone's message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BotReply {
+    pub id: i64,
+    pub source_message_id: String,
+    pub channel_id: String,
+    pub reply_message_id: String,
+    pub kind: ReplyKind,
+    /// the link the reply was sent for. for embed replies, the reply's content.
+    pub link: String,
+}
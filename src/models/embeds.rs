@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use fancy_regex::{Expander, Regex};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmbedRule {
+    pub id: i64,
+    pub name: String,
+    pub pattern: String,
+    pub template: String,
+    /// fixer hosts substituted for `{host}` in the template, in order of preference.
+    pub hosts: Vec<String>,
+}
+
+/// placeholder in a template for the rule's first healthy fixer host.
+pub const HOST_PLACEHOLDER: &str = "{host}";
+
+/// an embed rule with its pattern compiled, ready to rewrite links.
+#[derive(Clone, Debug)]
+pub struct CompiledEmbedRule {
+    pub name: String,
+    pub regex: Regex,
+    /// replacement url, with `$name` / `${name}` or `$1` / `${1}` referring to the pattern's
+    /// capture groups.
+    pub template: String,
+    pub hosts: Vec<String>,
+}
+
+impl CompiledEmbedRule {
+    /// compiles a rule, checking that the template only refers to groups the pattern has.
+    pub fn new(
+        name: &str,
+        pattern: &str,
+        template: &str,
+        hosts: Vec<String>,
+    ) -> Result<Self, String> {
+        let regex = Regex::new(pattern).map_err(|e| format!("invalid pattern: {}", e))?;
+
+        Expander::default()
+            .check(template, &regex)
+            .map_err(|e| format!("invalid template: {}", e))?;
+
+        Ok(CompiledEmbedRule {
+            name: name.to_string(),
+            regex,
+            template: template.to_string(),
+            hosts,
+        })
+    }
+
+    /// the template with `{host}` filled in by the first host `is_healthy` accepts.
+    ///
+    /// `None` if the template needs a host and none of them are up.
+    fn resolve_template(&self, is_healthy: impl Fn(&str) -> bool) -> Option<String> {
+        if !self.template.contains(HOST_PLACEHOLDER) {
+            return Some(self.template.clone());
+        }
+
+        self.hosts
+            .iter()
+            .find(|host| is_healthy(host))
+            .map(|host| self.template.replace(HOST_PLACEHOLDER, host))
+    }
+
+    /// every link in `text` matching this rule, with where it is in `text` and what it rewrites
+    /// into. the url is `None` if the template needs a host and none of them are up.
+    pub fn rewrite_matches(
+        &self,
+        text: &str,
+        is_healthy: impl Fn(&str) -> bool,
+    ) -> Vec<(Range<usize>, Option<String>)> {
+        let template = self.resolve_template(is_healthy);
+
+        self.regex
+            .captures_iter(text)
+            .filter_map(|captures| captures.ok())
+            .map(|captures| {
+                let url = template.as_ref().map(|template| {
+                    let mut url = String::new();
+                    captures.expand(template, &mut url);
+                    url
+                });
+
+                (captures.get(0).map_or(0..0, |m| m.start()..m.end()), url)
+            })
+            .collect()
+    }
+
+    /// the rewritten urls for every link in `text` matching this rule, with where each link is
+    /// in `text`.
+    pub fn rewrite_all(
+        &self,
+        text: &str,
+        is_healthy: impl Fn(&str) -> bool,
+    ) -> Vec<(Range<usize>, String)> {
+        self.rewrite_matches(text, is_healthy)
+            .into_iter()
+            .filter_map(|(link, url)| Some((link, url?)))
+            .collect()
+    }
+}
+
+impl TryFrom<&EmbedRule> for CompiledEmbedRule {
+    type Error = String;
+
+    fn try_from(rule: &EmbedRule) -> Result<Self, Self::Error> {
+        CompiledEmbedRule::new(
+            &rule.name,
+            &rule.pattern,
+            &rule.template,
+            rule.hosts.clone(),
+        )
+    }
+}
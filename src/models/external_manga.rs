@@ -0,0 +1,15 @@
+use mangadex_api_schema_rust::v5::MangaAttributes;
+use serde::Deserialize;
+
+/// response of the mangadex manga search, made without the client so that link filters can be
+/// passed.
+#[derive(Deserialize)]
+pub struct MangaSearchResponse {
+    pub data: Vec<MangaSearchResult>,
+}
+
+#[derive(Deserialize)]
+pub struct MangaSearchResult {
+    pub id: String,
+    pub attributes: MangaAttributes,
+}
@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+/// what the bot could find out about a page from its metadata.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkPreview {
+    pub url: String,
+    pub site_name: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub author: Option<String>,
+    pub color: Option<u32>,
+    /// where the page's oEmbed data is, if it advertises any.
+    pub oembed_url: Option<String>,
+}
+
+impl LinkPreview {
+    /// whether there is enough here to be worth an embed.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image.is_none()
+    }
+
+    /// fill in whatever the page's meta tags left out from its oEmbed data.
+    pub fn merge_oembed(&mut self, oembed: OEmbedResponse) {
+        self.title = self.title.take().or(oembed.title);
+        self.author = self.author.take().or(oembed.author_name);
+        self.site_name = self.site_name.take().or(oembed.provider_name);
+        self.image = self.image.take().or(oembed.thumbnail_url);
+    }
+}
+
+/// the fields of an oEmbed response the previews use.
+#[derive(Deserialize)]
+pub struct OEmbedResponse {
+    pub title: Option<String>,
+    pub author_name: Option<String>,
+    pub provider_name: Option<String>,
+    pub thumbnail_url: Option<String>,
+}
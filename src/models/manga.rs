@@ -1,4 +1,6 @@
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Manga {
@@ -6,4 +8,318 @@ pub struct Manga {
     pub manga_dex_id: uuid::fmt::Hyphenated,
     pub last_updated: OffsetDateTime,
     pub last_chapter_date: Option<OffsetDateTime>,
+    pub title: Option<String>,
+    pub status: Option<String>,
+}
+
+/// where the chapter tracker looks for new chapters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrackerSource {
+    /// check every manga in the database one by one.
+    #[default]
+    List,
+    /// read the logged-in mangadex user's followed manga feed.
+    Follows,
+}
+
+/// a tracked manga as written to and read from an export file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ExportedManga {
+    pub manga_dex_id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_updated: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_chapter_date: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub subscribers: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TrackingListExport {
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    pub manga: Vec<ExportedManga>,
+}
+
+const CSV_HEADER: [&str; 8] = [
+    "manga_dex_id",
+    "title",
+    "status",
+    "last_updated",
+    "last_chapter_date",
+    "languages",
+    "aliases",
+    "subscribers",
+];
+
+/// separator for list columns (languages, aliases, subscribers) in csv exports.
+const CSV_LIST_SEPARATOR: char = ';';
+/// escapes a literal separator (or itself) inside a list item.
+const CSV_LIST_ESCAPE: char = '\\';
+
+/// joins a list column, escaping separators inside the items.
+fn csv_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            item.replace(CSV_LIST_ESCAPE, "\\\\")
+                .replace(CSV_LIST_SEPARATOR, "\\;")
+        })
+        .collect::<Vec<String>>()
+        .join(&CSV_LIST_SEPARATOR.to_string())
+}
+
+/// splits a list column written by [`csv_list`], dropping empty items.
+fn csv_list_items(field: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut item = String::new();
+    let mut chars = field.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            CSV_LIST_ESCAPE => item.extend(chars.next()),
+            CSV_LIST_SEPARATOR => items.push(std::mem::take(&mut item)),
+            _ => item.push(c),
+        }
+    }
+    items.push(item);
+
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// splits csv text into records, handling quoted fields with embedded commas, quotes and newlines.
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records.retain(|record| record.iter().any(|field| !field.trim().is_empty()));
+    records
+}
+
+impl TrackingListExport {
+    pub fn to_csv(&self) -> String {
+        let mut csv = CSV_HEADER.join(",") + "\n";
+
+        for manga in &self.manga {
+            let timestamp = |t: Option<OffsetDateTime>| {
+                t.and_then(|t| t.format(&Rfc3339).ok()).unwrap_or_default()
+            };
+            let fields = [
+                manga.manga_dex_id.clone(),
+                manga.title.clone().unwrap_or_default(),
+                manga.status.clone().unwrap_or_default(),
+                timestamp(manga.last_updated),
+                timestamp(manga.last_chapter_date),
+                csv_list(&manga.languages),
+                csv_list(&manga.aliases),
+                csv_list(&manga.subscribers),
+            ];
+
+            csv += &fields
+                .iter()
+                .map(|f| csv_field(f))
+                .collect::<Vec<_>>()
+                .join(",");
+            csv += "\n";
+        }
+
+        csv
+    }
+
+    /// parses a csv export. columns are matched by header name, so they can be reordered or
+    /// left out, but `manga_dex_id` is required.
+    pub fn from_csv(text: &str) -> Result<Vec<ExportedManga>, String> {
+        let mut records = csv_records(text.trim_start_matches('\u{feff}')).into_iter();
+
+        let header = records.next().ok_or("the csv file is empty.")?;
+        let column = |name: &str| header.iter().position(|h| h.trim() == name);
+
+        let id_column =
+            column("manga_dex_id").ok_or("the csv file has no `manga_dex_id` column.")?;
+        let title_column = column("title");
+        let status_column = column("status");
+        let last_updated_column = column("last_updated");
+        let last_chapter_date_column = column("last_chapter_date");
+        let languages_column = column("languages");
+        let aliases_column = column("aliases");
+        let subscribers_column = column("subscribers");
+
+        let mut manga = vec![];
+
+        for (line, record) in records.enumerate() {
+            let get = |idx: Option<usize>| {
+                idx.and_then(|idx| record.get(idx))
+                    .map(|field| field.trim())
+                    .filter(|field| !field.is_empty())
+            };
+            let timestamp = |idx: Option<usize>| {
+                get(idx)
+                    .map(|field| {
+                        OffsetDateTime::parse(field, &Rfc3339).map_err(|_| {
+                            format!("invalid timestamp `{}` on row {}.", field, line + 2)
+                        })
+                    })
+                    .transpose()
+            };
+            let list = |idx: Option<usize>| get(idx).map(csv_list_items).unwrap_or_default();
+
+            manga.push(ExportedManga {
+                manga_dex_id: get(Some(id_column))
+                    .ok_or(format!("missing `manga_dex_id` on row {}.", line + 2))?
+                    .to_string(),
+                title: get(title_column).map(|s| s.to_string()),
+                status: get(status_column).map(|s| s.to_string()),
+                last_updated: timestamp(last_updated_column)?,
+                last_chapter_date: timestamp(last_chapter_date_column)?,
+                languages: list(languages_column),
+                aliases: list(aliases_column),
+                subscribers: list(subscribers_column),
+            });
+        }
+
+        Ok(manga)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(manga: Vec<ExportedManga>) -> TrackingListExport {
+        TrackingListExport {
+            exported_at: time::macros::datetime!(2026-10-19 12:00 UTC),
+            manga,
+        }
+    }
+
+    fn exported(id: &str) -> ExportedManga {
+        ExportedManga {
+            manga_dex_id: id.to_string(),
+            title: None,
+            status: None,
+            last_updated: None,
+            last_chapter_date: None,
+            languages: vec![],
+            aliases: vec![],
+            subscribers: vec![],
+        }
+    }
+
+    #[test]
+    fn csv_round_trips_awkward_fields() {
+        let manga = vec![
+            ExportedManga {
+                title: Some("\"oshi no ko\", vol. 1\nsecond line".to_string()),
+                status: Some("ongoing".to_string()),
+                last_updated: Some(time::macros::datetime!(2026-10-01 08:30 UTC)),
+                last_chapter_date: Some(time::macros::datetime!(2026-09-30 00:00 UTC)),
+                languages: vec!["en".to_string()],
+                aliases: vec![
+                    "a;b".to_string(),
+                    "back\\slash".to_string(),
+                    "comma, \"quoted\"".to_string(),
+                    "multi\nline".to_string(),
+                ],
+                subscribers: vec!["1".to_string(), "2".to_string()],
+                ..exported("a96676e5-8ae2-425e-b549-7f15dd34a6d8")
+            },
+            exported("c52b2ce3-7f95-469c-96b0-479524fb7a1a"),
+        ];
+
+        let csv = export(manga.clone()).to_csv();
+
+        assert_eq!(TrackingListExport::from_csv(&csv).unwrap(), manga);
+    }
+
+    #[test]
+    fn csv_list_escapes_separator() {
+        let items = vec!["a;b".to_string(), "c\\".to_string(), "d".to_string()];
+
+        assert_eq!(csv_list(&items), r"a\;b;c\\;d");
+        assert_eq!(csv_list_items(&csv_list(&items)), items);
+    }
+
+    #[test]
+    fn csv_records_handles_quotes_and_crlf() {
+        let records = csv_records("a,\"b,\"\"c\"\"\"\r\n\"d\ne\",f\r\n\r\n");
+
+        assert_eq!(
+            records,
+            vec![
+                vec!["a".to_string(), "b,\"c\"".to_string()],
+                vec!["d\ne".to_string(), "f".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_matches_columns_by_header() {
+        let csv = "\u{feff}aliases,manga_dex_id\nfoo;bar,some-id\n";
+
+        let manga = TrackingListExport::from_csv(csv).unwrap();
+
+        assert_eq!(
+            manga,
+            vec![ExportedManga {
+                aliases: vec!["foo".to_string(), "bar".to_string()],
+                ..exported("some-id")
+            }]
+        );
+    }
+
+    #[test]
+    fn csv_rejects_missing_id_and_bad_timestamps() {
+        assert!(TrackingListExport::from_csv("").is_err());
+        assert!(TrackingListExport::from_csv("title\nfoo\n").is_err());
+        assert!(TrackingListExport::from_csv("manga_dex_id,title\n,foo\n").is_err());
+        assert!(
+            TrackingListExport::from_csv("manga_dex_id,last_updated\nsome-id,yesterday\n").is_err()
+        );
+    }
 }
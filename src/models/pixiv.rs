@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+/// the envelope around pixiv's ajax responses. `body` is missing when `error` is set, e.g. for
+/// deleted or age-restricted posts.
+#[derive(Deserialize)]
+pub struct PixivAjaxResponse<T> {
+    pub error: bool,
+    pub body: Option<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PixivIllust {
+    pub page_count: usize,
+}
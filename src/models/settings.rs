@@ -0,0 +1,61 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, poise::ChoiceParameter, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub enum AdultPreviewMode {
+    /// send the cover as a spoilered attachment and spoiler the description.
+    #[name = "spoiler"]
+    Spoiler,
+    /// leave the cover out and spoiler the description.
+    #[name = "omit"]
+    Omit,
+    /// show the preview as-is.
+    #[name = "show"]
+    Show,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, poise::ChoiceParameter, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub enum LinkPreviewMode {
+    /// reply with the links rewritten to their fixer hosts.
+    #[name = "proxy"]
+    Proxy,
+    /// fetch the pages and reply with previews built from their metadata.
+    #[name = "native"]
+    Native,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, poise::ChoiceParameter, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub enum LinkReplyMode {
+    /// reply under the original message.
+    #[name = "reply"]
+    Reply,
+    /// repost the message with its links fixed through a webhook, and delete the original.
+    #[name = "repost"]
+    Repost,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuildSettings {
+    pub guild_id: String,
+    pub adult_manga_preview: AdultPreviewMode,
+    pub link_previews: LinkPreviewMode,
+    pub link_replies: LinkReplyMode,
+    /// how many minutes a shared link counts as already posted in its channel. 0 turns the
+    /// notices off.
+    pub duplicate_window: i64,
+}
+
+/// the duplicate window of guilds that never changed it, in minutes.
+pub const DEFAULT_DUPLICATE_WINDOW: i64 = 1440;
+
+impl GuildSettings {
+    pub fn new(guild_id: String) -> Self {
+        GuildSettings {
+            guild_id,
+            adult_manga_preview: AdultPreviewMode::Spoiler,
+            link_previews: LinkPreviewMode::Proxy,
+            link_replies: LinkReplyMode::Reply,
+            duplicate_window: DEFAULT_DUPLICATE_WINDOW,
+        }
+    }
+}
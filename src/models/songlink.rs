@@ -1,16 +1,12 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SonglinkResponse {
-    pub links_by_platform: LinkByPlatform,
-}
-
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LinkByPlatform {
-    pub spotify: Option<LinkByPlatformInner>,
-    pub youtube_music: Option<LinkByPlatformInner>,
+    /// the song's links, keyed by songlink's name for each platform, e.g. `appleMusic`.
+    pub links_by_platform: HashMap<String, LinkByPlatformInner>,
 }
 
 #[derive(Deserialize, Serialize)]
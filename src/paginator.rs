@@ -0,0 +1,407 @@
+use futures::future::BoxFuture;
+use poise::serenity_prelude::{self as serenity, *};
+
+use crate::{Data, Error};
+
+/// prefix of every paginator custom id.
+///
+/// custom ids look like `pg:<kind>:<author>:<page>:<action>`, so any interaction with a paginated
+/// message can be handled from the event handler without a live collector, even after a restart.
+const CUSTOM_ID_PREFIX: &str = "pg";
+
+/// discord only allows 25 options in a select menu.
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// a list that can be paginated. pages are rebuilt with `pages` on every interaction.
+#[derive(Clone, Copy)]
+pub struct PageSource {
+    /// names the list in custom ids, so it can't contain `:`.
+    pub kind: &'static str,
+    pub pages: for<'a> fn(&'a Data) -> BoxFuture<'a, Result<Vec<Page>, Error>>,
+}
+
+/// a single page of a paginated list.
+pub struct Page {
+    /// short label shown in the page select menu.
+    pub label: String,
+    pub embed: CreateEmbed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    First,
+    Prev,
+    Next,
+    Last,
+    Jump,
+    Select,
+}
+
+impl Action {
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::First => "first",
+            Action::Prev => "prev",
+            Action::Next => "next",
+            Action::Last => "last",
+            Action::Jump => "jump",
+            Action::Select => "select",
+        }
+    }
+
+    fn from_str(action: &str) -> Option<Self> {
+        match action {
+            "first" => Some(Action::First),
+            "prev" => Some(Action::Prev),
+            "next" => Some(Action::Next),
+            "last" => Some(Action::Last),
+            "jump" => Some(Action::Jump),
+            "select" => Some(Action::Select),
+            _ => None,
+        }
+    }
+}
+
+/// paginator state, encoded in a component's custom id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PaginatorId<'a> {
+    kind: &'a str,
+    author_id: UserId,
+    page: usize,
+    action: Action,
+}
+
+impl<'a> PaginatorId<'a> {
+    fn parse(custom_id: &'a str) -> Option<Self> {
+        let mut parts = custom_id.split(':');
+
+        if parts.next()? != CUSTOM_ID_PREFIX {
+            return None;
+        }
+
+        let kind = parts.next().filter(|kind| !kind.is_empty())?;
+        let author_id = UserId::new(parts.next()?.parse().ok().filter(|id| *id != 0)?);
+        let page = parts.next()?.parse().ok()?;
+        let action = Action::from_str(parts.next()?)?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(PaginatorId {
+            kind,
+            author_id,
+            page,
+            action,
+        })
+    }
+
+    fn custom_id(kind: &str, author_id: UserId, page: usize, action: Action) -> String {
+        format!(
+            "{}:{}:{}:{}:{}",
+            CUSTOM_ID_PREFIX,
+            kind,
+            author_id,
+            page,
+            action.as_str()
+        )
+    }
+}
+
+fn page_embed(pages: &[Page], page: usize) -> CreateEmbed {
+    pages[page]
+        .embed
+        .clone()
+        .footer(CreateEmbedFooter::new(format!(
+            "page {}/{}",
+            page + 1,
+            pages.len()
+        )))
+}
+
+fn page_components(
+    kind: &str,
+    author_id: UserId,
+    pages: &[Page],
+    page: usize,
+) -> Vec<CreateActionRow> {
+    if pages.len() <= 1 {
+        return vec![];
+    }
+
+    let last_page = pages.len() - 1;
+    let id = |action| PaginatorId::custom_id(kind, author_id, page, action);
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(id(Action::First))
+            .emoji('⏮')
+            .disabled(page == 0),
+        CreateButton::new(id(Action::Prev))
+            .emoji('◀')
+            .disabled(page == 0),
+        CreateButton::new(id(Action::Jump))
+            .emoji('🔢')
+            .style(ButtonStyle::Secondary),
+        CreateButton::new(id(Action::Next))
+            .emoji('▶')
+            .disabled(page == last_page),
+        CreateButton::new(id(Action::Last))
+            .emoji('⏭')
+            .disabled(page == last_page),
+    ]);
+
+    // show a window of pages around the current one when there are too many to list.
+    let start = page
+        .saturating_sub(MAX_SELECT_OPTIONS / 2)
+        .min(pages.len().saturating_sub(MAX_SELECT_OPTIONS));
+    let end = (start + MAX_SELECT_OPTIONS).min(pages.len());
+
+    let options = (start..end)
+        .map(|idx| {
+            let mut label = format!("page {}", idx + 1);
+            if !pages[idx].label.is_empty() {
+                label = format!("{} - {}", label, pages[idx].label);
+            }
+
+            CreateSelectMenuOption::new(
+                label.chars().take(100).collect::<String>(),
+                idx.to_string(),
+            )
+            .default_selection(idx == page)
+        })
+        .collect();
+
+    let select = CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(id(Action::Select), CreateSelectMenuKind::String { options })
+            .placeholder("jump to a page..."),
+    );
+
+    vec![buttons, select]
+}
+
+/// builds the first reply of a paginated list, starting at the first page.
+///
+/// `pages` must not be empty.
+pub fn reply(source: &PageSource, author_id: UserId, pages: &[Page]) -> poise::CreateReply {
+    poise::CreateReply::default()
+        .embed(page_embed(pages, 0))
+        .components(page_components(source.kind, author_id, pages, 0))
+}
+
+fn update_response(
+    kind: &str,
+    author_id: UserId,
+    pages: &[Page],
+    page: usize,
+) -> CreateInteractionResponse {
+    if pages.is_empty() {
+        return CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .content("this list is now empty.")
+                .embeds(vec![])
+                .components(vec![]),
+        );
+    }
+
+    let page = page.min(pages.len() - 1);
+
+    CreateInteractionResponse::UpdateMessage(
+        CreateInteractionResponseMessage::new()
+            .embed(page_embed(pages, page))
+            .components(page_components(kind, author_id, pages, page)),
+    )
+}
+
+/// the state in `custom_id` and the source of the list it pages through, if it's one of ours.
+fn find_paginator<'s, 'a>(
+    sources: &'s [PageSource],
+    custom_id: &'a str,
+) -> Option<(PaginatorId<'a>, &'s PageSource)> {
+    let id = PaginatorId::parse(custom_id)?;
+    let source = sources.iter().find(|source| source.kind == id.kind)?;
+
+    Some((id, source))
+}
+
+fn not_author_response() -> CreateInteractionResponse {
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content("you cannot interact with another user's invoked command!")
+            .ephemeral(true),
+    )
+}
+
+/// handles button, select menu and modal interactions on paginated messages built from `sources`.
+///
+/// interactions whose custom id does not belong to one of their paginators are ignored.
+pub async fn handle_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    interaction: &Interaction,
+    sources: &[PageSource],
+) -> Result<(), Error> {
+    match interaction {
+        Interaction::Component(press) => {
+            let Some((id, source)) = find_paginator(sources, &press.data.custom_id) else {
+                return Ok(());
+            };
+
+            if press.user.id != id.author_id {
+                press
+                    .create_response(ctx, not_author_response())
+                    .await
+                    .inspect_err(
+                        |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+                    )?;
+
+                return Ok(());
+            }
+
+            if id.action == Action::Jump {
+                press
+                    .create_response(
+                        ctx,
+                        CreateInteractionResponse::Modal(
+                            CreateModal::new(press.data.custom_id.clone(), "jump to page")
+                                .components(vec![CreateActionRow::InputText(
+                                    CreateInputText::new(InputTextStyle::Short, "page", "page")
+                                        .placeholder(format!("currently on page {}", id.page + 1))
+                                        .min_length(1)
+                                        .max_length(5),
+                                )]),
+                        ),
+                    )
+                    .await
+                    .inspect_err(
+                        |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+                    )?;
+
+                return Ok(());
+            }
+
+            let pages = (source.pages)(data).await?;
+            let last_page = pages.len().saturating_sub(1);
+
+            let page = match (id.action, &press.data.kind) {
+                (Action::First, _) => 0,
+                (Action::Prev, _) => id.page.saturating_sub(1),
+                (Action::Next, _) => id.page + 1,
+                (Action::Last, _) => last_page,
+                (Action::Select, ComponentInteractionDataKind::StringSelect { values }) => values
+                    .first()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(id.page),
+                _ => id.page,
+            };
+
+            press
+                .create_response(ctx, update_response(id.kind, id.author_id, &pages, page))
+                .await
+                .inspect_err(
+                    |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+                )?;
+        }
+        Interaction::Modal(submit) => {
+            let Some((id, source)) = find_paginator(sources, &submit.data.custom_id) else {
+                return Ok(());
+            };
+
+            if id.action != Action::Jump {
+                return Ok(());
+            }
+
+            if submit.user.id != id.author_id {
+                submit
+                    .create_response(ctx, not_author_response())
+                    .await
+                    .inspect_err(
+                        |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+                    )?;
+
+                return Ok(());
+            }
+
+            let input = submit
+                .data
+                .components
+                .iter()
+                .flat_map(|row| row.components.iter())
+                .find_map(|component| match component {
+                    ActionRowComponent::InputText(input) => input.value.clone(),
+                    _ => None,
+                })
+                .unwrap_or_default();
+
+            let pages = (source.pages)(data).await?;
+
+            let response = match input.trim().parse::<usize>() {
+                Ok(page) if (1..=pages.len()).contains(&page) => {
+                    update_response(id.kind, id.author_id, &pages, page - 1)
+                }
+                _ => CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(format!(
+                            "`{}` is not a valid page. pick a page between 1 and {}.",
+                            input.trim(),
+                            pages.len()
+                        ))
+                        .ephemeral(true),
+                ),
+            };
+
+            submit.create_response(ctx, response).await.inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_its_own_custom_ids() {
+        let author_id = UserId::new(42);
+
+        for action in [
+            Action::First,
+            Action::Prev,
+            Action::Next,
+            Action::Last,
+            Action::Jump,
+            Action::Select,
+        ] {
+            assert_eq!(
+                PaginatorId::parse(&PaginatorId::custom_id("manga", author_id, 3, action)),
+                Some(PaginatorId {
+                    kind: "manga",
+                    author_id,
+                    page: 3,
+                    action,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_other_custom_ids() {
+        for custom_id in [
+            "pv:42:12345678:2:5:next",
+            "pg",
+            "pg::42:3:next",
+            "pg:manga:0:3:next",
+            "pg:manga:abc:3:next",
+            "pg:manga:42:-1:next",
+            "pg:manga:42:3:skip",
+            "pg:manga:42:3",
+            "pg:manga:42:3:next:extra",
+            "pgx:manga:42:3:next",
+        ] {
+            assert_eq!(PaginatorId::parse(custom_id), None, "{custom_id}");
+        }
+    }
+}
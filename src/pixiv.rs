@@ -0,0 +1,384 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use poise::serenity_prelude::{self as serenity, *};
+
+use crate::Error;
+use crate::constants::embeds::PIXIV_ARTWORK_URL_REGEX;
+use crate::embed_rules::MatchedLink;
+use crate::models::pixiv::{PixivAjaxResponse, PixivIllust};
+
+/// prefix of the gallery buttons' custom ids,
+/// `pv:<author>:<artwork>:<page>:<page count>:<action>`.
+const GALLERY_CUSTOM_ID_PREFIX: &str = "pv";
+
+const PIXIV_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// a rewritten artwork link on its own line of a reply, split from the page it shows.
+///
+/// which links are pixiv artworks is up to the embed rules, so this only looks at the links they
+/// were rewritten into.
+static GALLERY_URL_REGEX: LazyLock<fancy_regex::Regex> = LazyLock::new(|| {
+    fancy_regex::Regex::new(
+        r"^(?P<base>https?://[^/\s]+/(?:[a-z]{2}/)?artworks/(?P<id>\d+))(?:/(?P<idx>\d+))?$",
+    )
+    .unwrap()
+});
+
+/// a multi-page pixiv post being paged through. pages start at 1, like phixiv's.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gallery {
+    pub artwork_id: u64,
+    pub page: usize,
+    pub page_count: usize,
+}
+
+impl Gallery {
+    fn custom_id(&self, author_id: UserId, action: &str) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            GALLERY_CUSTOM_ID_PREFIX,
+            author_id,
+            self.artwork_id,
+            self.page,
+            self.page_count,
+            action
+        )
+    }
+
+    /// the author, gallery and action of a gallery button's custom id, if it is one.
+    fn parse_custom_id(custom_id: &str) -> Option<(UserId, Gallery, &str)> {
+        let mut parts = custom_id.split(':');
+
+        if parts.next() != Some(GALLERY_CUSTOM_ID_PREFIX) {
+            return None;
+        }
+
+        let author_id = parts
+            .next()
+            .and_then(|id| id.parse().ok())
+            .filter(|id| *id != 0)
+            .map(UserId::new)?;
+        let artwork_id = parts.next().and_then(|id| id.parse::<u64>().ok())?;
+        let page = parts.next().and_then(|page| page.parse::<usize>().ok())?;
+        let page_count = parts.next().and_then(|count| count.parse::<usize>().ok())?;
+        let action = parts.next()?;
+
+        Some((
+            author_id,
+            Gallery {
+                artwork_id,
+                page,
+                page_count,
+            },
+            action,
+        ))
+    }
+
+    /// point the lines of `content` linking to the artwork at the gallery's page, or `None` if no
+    /// line links to it anymore.
+    fn page_content(&self, content: &str) -> Option<String> {
+        let mut paged = false;
+
+        let content = content
+            .lines()
+            .map(|line| match GALLERY_URL_REGEX.captures(line) {
+                Ok(Some(captures)) if captures["id"] == self.artwork_id.to_string() => {
+                    paged = true;
+                    format!("{}/{}", &captures["base"], self.page)
+                }
+                _ => line.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        paged.then_some(content)
+    }
+
+    /// the buttons to page through the post, usable by `author_id` only.
+    pub fn components(&self, author_id: UserId) -> Vec<CreateActionRow> {
+        vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(self.custom_id(author_id, "prev"))
+                .emoji('◀')
+                .disabled(self.page <= 1),
+            CreateButton::new(self.custom_id(author_id, "page"))
+                .label(format!("{}/{}", self.page, self.page_count))
+                .style(ButtonStyle::Secondary)
+                .disabled(true),
+            CreateButton::new(self.custom_id(author_id, "next"))
+                .emoji('▶')
+                .disabled(self.page >= self.page_count),
+        ])]
+    }
+}
+
+async fn fetch_page_count(client: &reqwest::Client, artwork_id: u64) -> Option<usize> {
+    let res = client
+        .get(format!("https://www.pixiv.net/ajax/illust/{}", artwork_id))
+        .timeout(PIXIV_FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .inspect_err(|e| tracing::warn!(err = ?e, artwork_id = %artwork_id, "an error occurred when fetching pixiv artwork"))
+        .ok()?
+        .json::<PixivAjaxResponse<PixivIllust>>()
+        .await
+        .inspect_err(|e| tracing::warn!(err = ?e, artwork_id = %artwork_id, "an error occurred when decoding pixiv artwork"))
+        .ok()?;
+
+    if res.error {
+        return None;
+    }
+
+    res.body.map(|illust| illust.page_count)
+}
+
+/// the artwork a pixiv link was rewritten to show, and the page the link pointed to, if any.
+///
+/// only pixiv artwork links the reply shows on a line of their own count, so spoilered links and
+/// links that weren't rewritten are skipped.
+fn artwork_link(link: &MatchedLink) -> Option<(u64, Option<usize>)> {
+    if link.spoiler
+        || !PIXIV_ARTWORK_URL_REGEX
+            .is_match(&link.link)
+            .unwrap_or(false)
+    {
+        return None;
+    }
+
+    let url = link.url.as_ref().filter(|url| **url != link.link)?;
+    let captures = GALLERY_URL_REGEX.captures(url).ok()??;
+    let artwork_id = captures["id"].parse::<u64>().ok()?;
+    let page = captures
+        .name("idx")
+        .and_then(|idx| idx.as_str().parse::<usize>().ok());
+
+    Some((artwork_id, page))
+}
+
+/// the first multi-page pixiv post among `links`, starting at the page the link pointed to.
+///
+/// the page counts are fetched all at once, so a slow artwork doesn't hold up the others.
+pub async fn find_gallery<'a>(
+    client: &reqwest::Client,
+    links: impl IntoIterator<Item = &'a MatchedLink>,
+) -> Option<Gallery> {
+    let artworks = links
+        .into_iter()
+        .filter_map(artwork_link)
+        .collect::<Vec<_>>();
+
+    let page_counts = futures::future::join_all(
+        artworks
+            .iter()
+            .map(|(artwork_id, _)| fetch_page_count(client, *artwork_id)),
+    )
+    .await;
+
+    artworks
+        .into_iter()
+        .zip(page_counts)
+        .find_map(|((artwork_id, page), page_count)| {
+            let page_count = page_count.filter(|count| *count > 1)?;
+
+            Some(Gallery {
+                artwork_id,
+                page: page.unwrap_or(1).clamp(1, page_count),
+                page_count,
+            })
+        })
+}
+
+/// handles the prev and next buttons on pixiv galleries by pointing the artwork's link in the
+/// reply at another page.
+///
+/// interactions whose custom id does not belong to a gallery are ignored.
+pub async fn handle_gallery_interaction(
+    ctx: &serenity::Context,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    let Interaction::Component(press) = interaction else {
+        return Ok(());
+    };
+
+    let Some((author_id, gallery, action)) = Gallery::parse_custom_id(&press.data.custom_id) else {
+        return Ok(());
+    };
+
+    if press.user.id != author_id {
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("only the person who posted this can flip through it!")
+                        .ephemeral(true),
+                ),
+            )
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+
+        return Ok(());
+    }
+
+    let page = match action {
+        "prev" => gallery.page.saturating_sub(1),
+        "next" => gallery.page + 1,
+        _ => gallery.page,
+    }
+    .clamp(1, gallery.page_count.max(1));
+
+    let gallery = Gallery { page, ..gallery };
+
+    // the link is gone if the reply was edited since, so the buttons have nothing left to page.
+    let (content, components) = match gallery.page_content(&press.message.content) {
+        Some(content) => (content, gallery.components(author_id)),
+        None => (press.message.content.clone(), vec![]),
+    };
+
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(components)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            ),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when creating response"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GALLERY: Gallery = Gallery {
+        artwork_id: 12345678,
+        page: 2,
+        page_count: 5,
+    };
+
+    fn matched(link: &str, url: Option<&str>, spoiler: bool) -> MatchedLink {
+        MatchedLink {
+            link: link.to_string(),
+            url: url.map(str::to_string),
+            spoiler,
+        }
+    }
+
+    #[test]
+    fn parses_its_own_custom_ids() {
+        let author_id = UserId::new(42);
+
+        for action in ["prev", "page", "next"] {
+            assert_eq!(
+                Gallery::parse_custom_id(&GALLERY.custom_id(author_id, action)),
+                Some((author_id, GALLERY, action))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_other_custom_ids() {
+        for custom_id in [
+            "ur:42",
+            "pv",
+            "pv:0:12345678:2:5:next",
+            "pv:42:abc:2:5:next",
+            "pv:42:12345678:2:5",
+            "pvx:42:12345678:2:5:next",
+        ] {
+            assert_eq!(Gallery::parse_custom_id(custom_id), None, "{custom_id}");
+        }
+    }
+
+    #[test]
+    fn pages_only_the_artworks_own_lines() {
+        let content = "look at this 🎨\n\
+                       https://phixiv.net/en/artworks/12345678/1\n\
+                       https://phixiv.net/artworks/87654321\n\
+                       inline https://phixiv.net/artworks/12345678 stays";
+
+        assert_eq!(
+            GALLERY.page_content(content).as_deref(),
+            Some(
+                "look at this 🎨\n\
+                 https://phixiv.net/en/artworks/12345678/2\n\
+                 https://phixiv.net/artworks/87654321\n\
+                 inline https://phixiv.net/artworks/12345678 stays"
+            )
+        );
+        assert_eq!(
+            GALLERY.page_content("https://ppxiv.net/artworks/12345678"),
+            Some("https://ppxiv.net/artworks/12345678/2".to_string())
+        );
+    }
+
+    #[test]
+    fn stops_paging_once_the_link_is_gone() {
+        assert_eq!(
+            GALLERY.page_content("https://phixiv.net/artworks/87654321\nno link here"),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_artworks_in_rewritten_pixiv_links() {
+        let cases = [
+            (
+                matched(
+                    "https://www.pixiv.net/en/artworks/12345678",
+                    Some("https://phixiv.net/en/artworks/12345678"),
+                    false,
+                ),
+                Some((12345678, None)),
+            ),
+            (
+                matched(
+                    "https://pixiv.net/artworks/12345678/3",
+                    Some("https://phixiv.net/artworks/12345678/3"),
+                    false,
+                ),
+                Some((12345678, Some(3))),
+            ),
+            (
+                matched(
+                    "https://www.pixiv.net/artworks/12345678",
+                    Some("https://phixiv.net/artworks/12345678"),
+                    true,
+                ),
+                None,
+            ),
+            (
+                matched("https://www.pixiv.net/artworks/12345678", None, false),
+                None,
+            ),
+            (
+                matched(
+                    "https://www.pixiv.net/member_illust.php?illust_id=12345678",
+                    Some("https://phixiv.net/member_illust.php?illust_id=12345678"),
+                    false,
+                ),
+                None,
+            ),
+            (
+                matched(
+                    "https://example.com/artworks/12345678",
+                    Some("https://fixer.example.com/artworks/12345678"),
+                    false,
+                ),
+                None,
+            ),
+        ];
+
+        for (link, expected) in cases {
+            assert_eq!(artwork_link(&link), expected, "{}", link.link);
+        }
+    }
+}
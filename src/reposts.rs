@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use poise::serenity_prelude::{self as serenity, *};
+use sqlx::{Pool, Sqlite};
+
+use crate::bot_replies::get_replies;
+use crate::{Data, Error};
+
+/// the webhooks the bot reposts messages through, by channel.
+pub type Webhooks = Arc<RwLock<HashMap<ChannelId, Webhook>>>;
+
+const UNDO_REPOST_CUSTOM_ID_PREFIX: &str = "ur";
+
+const WEBHOOK_NAME: &str = "mikisayaka";
+
+/// how many days reposts can be undone for. the original content is only kept until then.
+const REPOST_RETENTION_DAYS: i64 = 30;
+
+/// whether a message can be reposted without losing anything webhooks can't recreate.
+fn can_repost(message: &Message) -> bool {
+    message.guild_id.is_some()
+        && message.edited_timestamp.is_none()
+        && message.attachments.is_empty()
+        && message.sticker_items.is_empty()
+        && message.poll.is_none()
+        && message.message_reference.is_none()
+        // quotes are answered after the link handlers, and need the original to reply to.
+        && !message.content.starts_with("... ")
+}
+
+/// whether the bot can repost in a channel: it needs to manage webhooks to repost, and manage
+/// messages to delete the original.
+fn can_manage_channel(ctx: &serenity::Context, guild_id: GuildId, channel_id: ChannelId) -> bool {
+    let bot_id = ctx.cache.current_user().id;
+
+    let Some(guild) = ctx.cache.guild(guild_id) else {
+        return false;
+    };
+
+    let (Some(channel), Some(member)) =
+        (guild.channels.get(&channel_id), guild.members.get(&bot_id))
+    else {
+        return false;
+    };
+
+    guild
+        .user_permissions_in(channel, member)
+        .contains(Permissions::MANAGE_WEBHOOKS | Permissions::MANAGE_MESSAGES)
+}
+
+/// the bot's webhook in a channel, creating one if there is none yet.
+async fn channel_webhook(
+    ctx: &serenity::Context,
+    data: &Data,
+    channel_id: ChannelId,
+) -> Result<Webhook, serenity::Error> {
+    if let Some(webhook) = data.webhooks.read().unwrap().get(&channel_id) {
+        return Ok(webhook.clone());
+    }
+
+    let bot_id = ctx.cache.current_user().id;
+
+    let existing = channel_id
+        .webhooks(ctx)
+        .await
+        .inspect_err(|e| tracing::warn!(err = ?e, channel_id = %channel_id, "an error occurred when fetching webhooks"))?
+        .into_iter()
+        .find(|webhook| {
+            webhook.token.is_some() && webhook.user.as_ref().is_some_and(|user| user.id == bot_id)
+        });
+
+    let webhook = match existing {
+        Some(webhook) => webhook,
+        None => channel_id
+            .create_webhook(ctx, CreateWebhook::new(WEBHOOK_NAME))
+            .await
+            .inspect_err(|e| tracing::warn!(err = ?e, channel_id = %channel_id, "an error occurred when creating webhook"))?,
+    };
+
+    data.webhooks
+        .write()
+        .unwrap()
+        .insert(channel_id, webhook.clone());
+
+    Ok(webhook)
+}
+
+async fn record_repost(db: &Pool<Sqlite>, repost: &Message, original: &Message) {
+    let message_id = repost.id.to_string();
+    let channel_id = repost.channel_id.to_string();
+    let author_id = original.author.id.to_string();
+    let now = time::OffsetDateTime::now_utc();
+    let created_at = time::OffsetDateTime::new_utc(now.date(), now.time());
+
+    let _ = sqlx::query!(
+        r#"
+            INSERT INTO
+                reposts (message_id, channel_id, author_id, content, created_at)
+            VALUES
+                ($1, $2, $3, $4, $5);
+        "#,
+        message_id,
+        channel_id,
+        author_id,
+        original.content,
+        created_at,
+    )
+    .execute(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, message_id = %repost.id, "an error occurred when recording repost"),
+    );
+}
+
+/// stop tracking a repost, e.g. after it was deleted.
+pub async fn forget_repost(db: &Pool<Sqlite>, message_id: MessageId) {
+    let message_id = message_id.to_string();
+
+    let _ = sqlx::query!(
+        r#"
+            DELETE FROM reposts
+            WHERE message_id = $1;
+        "#,
+        message_id,
+    )
+    .execute(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, message_id = %message_id, "an error occurred when forgetting repost"),
+    );
+}
+
+/// forget reposts older than [`REPOST_RETENTION_DAYS`], returning how many were forgotten.
+pub async fn prune_old_reposts(db: &Pool<Sqlite>) -> Result<u64, sqlx::Error> {
+    let now = time::OffsetDateTime::now_utc();
+    let cutoff = time::OffsetDateTime::new_utc(now.date(), now.time())
+        - time::Duration::days(REPOST_RETENTION_DAYS);
+
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM reposts
+            WHERE created_at < $1;
+        "#,
+        cutoff,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// repost a message through the channel's webhook under its author's name and avatar, with
+/// `content` and `embeds` in place of the original, then delete the original.
+///
+/// returns the repost, if the message was reposted. messages that can't be reposted faithfully,
+/// messages the bot already replied to, and channels the bot lacks the permissions for are left
+/// alone so the caller can reply instead.
+pub async fn repost_message(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &Message,
+    content: &str,
+    embeds: Vec<CreateEmbed>,
+) -> anyhow::Result<Option<Message>> {
+    if !can_repost(message) {
+        return Ok(None);
+    }
+
+    let Some(channel) = message.channel(ctx).await?.guild() else {
+        return Ok(None);
+    };
+
+    // threads share their parent channel's webhooks.
+    let (webhook_channel_id, thread_id) = match channel.kind {
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread => {
+            let Some(parent_id) = channel.parent_id else {
+                return Ok(None);
+            };
+
+            (parent_id, Some(channel.id))
+        }
+        _ => (channel.id, None),
+    };
+
+    if !can_manage_channel(ctx, channel.guild_id, webhook_channel_id) {
+        tracing::debug!(channel_id = %webhook_channel_id, "missing permissions to repost message");
+        return Ok(None);
+    }
+
+    // deleting the original would take the other replies to it down with it.
+    if !get_replies(&data.db, message.id).await?.is_empty() {
+        return Ok(None);
+    }
+
+    let Ok(webhook) = channel_webhook(ctx, data, webhook_channel_id).await else {
+        return Ok(None);
+    };
+
+    let username = message
+        .member
+        .as_ref()
+        .and_then(|member| member.nick.clone())
+        .unwrap_or_else(|| message.author.display_name().to_string());
+
+    let mut builder = ExecuteWebhook::new()
+        .content(content)
+        .embeds(embeds)
+        .username(username)
+        .avatar_url(message.author.face())
+        .allowed_mentions(CreateAllowedMentions::new())
+        .components(vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(format!(
+                "{}:{}",
+                UNDO_REPOST_CUSTOM_ID_PREFIX, message.author.id
+            ))
+            .label("undo")
+            .style(ButtonStyle::Secondary),
+        ])]);
+
+    if let Some(thread_id) = thread_id {
+        builder = builder.in_thread(thread_id);
+    }
+
+    let repost = match webhook.execute(ctx, true, builder).await {
+        Ok(Some(repost)) => repost,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            tracing::warn!(err = ?e, channel_id = %webhook_channel_id, "an error occurred when reposting message");
+
+            // the webhook may have been deleted, so look it up again next time.
+            data.webhooks.write().unwrap().remove(&webhook_channel_id);
+
+            return Ok(None);
+        }
+    };
+
+    record_repost(&data.db, &repost, message).await;
+
+    message.delete(ctx).await.inspect_err(
+        |e| tracing::error!(err = ?e, "an error occurred when deleting reposted message"),
+    )?;
+
+    Ok(Some(repost))
+}
+
+/// handles the undo button on reposts by putting the original message back.
+///
+/// interactions whose custom id does not belong to an undo button are ignored.
+pub async fn handle_undo_repost_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    interaction: &Interaction,
+) -> Result<(), Error> {
+    let Interaction::Component(press) = interaction else {
+        return Ok(());
+    };
+
+    let Some(author_id) = press
+        .data
+        .custom_id
+        .strip_prefix(UNDO_REPOST_CUSTOM_ID_PREFIX)
+        .and_then(|id| id.strip_prefix(':'))
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+
+    if press.user.id != author_id {
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("only the author can undo this!")
+                        .ephemeral(true),
+                ),
+            )
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+
+        return Ok(());
+    }
+
+    let message_id = press.message.id.to_string();
+
+    let repost = sqlx::query!(
+        r#"
+            SELECT
+                content
+            FROM reposts
+            WHERE message_id = $1;
+        "#,
+        message_id,
+    )
+    .fetch_optional(&data.db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, message_id = %message_id, "an error occurred when fetching repost"),
+    )?;
+
+    let Some(repost) = repost else {
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("i don't remember what this message was anymore...")
+                        .ephemeral(true),
+                ),
+            )
+            .await
+            .inspect_err(
+                |e| tracing::error!(err = ?e, "an error occurred when creating response"),
+            )?;
+
+        return Ok(());
+    };
+
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(repost.content)
+                    .embeds(vec![])
+                    .components(vec![])
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            ),
+        )
+        .await
+        .inspect_err(|e| tracing::error!(err = ?e, "an error occurred when creating response"))?;
+
+    forget_repost(&data.db, press.message.id).await;
+
+    Ok(())
+}
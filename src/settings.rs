@@ -0,0 +1,34 @@
+use poise::serenity_prelude::GuildId;
+use sqlx::{Pool, Sqlite};
+
+use crate::models::settings::{AdultPreviewMode, GuildSettings, LinkPreviewMode, LinkReplyMode};
+
+/// fetch the settings for a guild, falling back to the defaults if none were saved yet.
+pub async fn get_guild_settings(
+    db: &Pool<Sqlite>,
+    guild_id: GuildId,
+) -> Result<GuildSettings, sqlx::Error> {
+    let guild_id = guild_id.to_string();
+
+    let settings = sqlx::query_as!(
+        GuildSettings,
+        r#"
+            SELECT
+                guild_id,
+                adult_manga_preview AS "adult_manga_preview: AdultPreviewMode",
+                link_previews AS "link_previews: LinkPreviewMode",
+                link_replies AS "link_replies: LinkReplyMode",
+                duplicate_window
+            FROM guild_settings
+            WHERE guild_id = $1;
+        "#,
+        guild_id
+    )
+    .fetch_optional(db)
+    .await
+    .inspect_err(
+        |e| tracing::error!(err = ?e, guild_id = %guild_id, "an error occurred when fetching guild settings"),
+    )?;
+
+    Ok(settings.unwrap_or_else(|| GuildSettings::new(guild_id)))
+}
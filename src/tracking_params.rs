@@ -0,0 +1,58 @@
+use reqwest::Url;
+
+use crate::constants::embeds::{LINK_REGEX, TRACKING_PARAMS};
+
+fn is_tracking_param(host: &str, key: &str) -> bool {
+    TRACKING_PARAMS
+        .iter()
+        .filter(|(params_host, _)| {
+            *params_host == "*"
+                || host == *params_host
+                || host.ends_with(&format!(".{}", params_host))
+        })
+        .flat_map(|(_, params)| params.iter())
+        .any(|param| match param.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == *param,
+        })
+}
+
+/// the link without its tracking parameters, or `None` if it doesn't have any.
+pub fn clean_url(link: &str) -> Option<String> {
+    let mut url = Url::parse(link).ok()?;
+    let host = url.host_str()?.to_lowercase();
+
+    let pairs = url
+        .query_pairs()
+        .into_owned()
+        .collect::<Vec<(String, String)>>();
+    let kept = pairs
+        .iter()
+        .filter(|(key, _)| !is_tracking_param(&host, key))
+        .collect::<Vec<&(String, String)>>();
+
+    if kept.len() == pairs.len() {
+        return None;
+    }
+
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+
+    Some(url.to_string())
+}
+
+/// `text` with the tracking parameters stripped from every link in it.
+pub fn clean_links(text: &str) -> String {
+    let mut cleaned = text.to_string();
+
+    for link in LINK_REGEX.find_iter(text).filter_map(|link| link.ok()) {
+        if let Some(url) = clean_url(link.as_str()) {
+            cleaned = cleaned.replacen(link.as_str(), &url, 1);
+        }
+    }
+
+    cleaned
+}